@@ -3,9 +3,10 @@ use codec::Codec;
 use jsonrpsee::core::RpcResult;
 use runtime_api::chain::ChainDataProvider as RuntimeChainDataProvider;
 use sc_client_api::BlockBackend;
-use sp_api::{BlockT, ProvideRuntimeApi};
+use sp_api::{ApiExt, BlockT, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
-use sp_rpc::{BlockchainStats, CharTrait, GenesisData};
+use sp_common::types::CommunityId;
+use sp_rpc::{BlockchainStats, CharTrait, GenesisData, PhoneVerifier};
 use sp_runtime::generic::SignedBlock;
 use std::sync::Arc;
 
@@ -65,14 +66,48 @@ where
 		from_index: Option<u32>,
 		limit: Option<u32>,
 		at: Option<<Block as BlockT>::Hash>,
+		community_id: Option<CommunityId>,
 	) -> RpcResult<Vec<CharTrait>> {
 		let api = self.client.runtime_api();
 		let at = at.unwrap_or_else(|| self.client.info().best_hash);
 
-		let char_traits = api
-			.get_char_traits(at, from_index, limit)
-			.map_err(|e| map_err(e, "Failed to get char traits"))?;
+		let api_version = api
+			.api_version::<dyn RuntimeChainDataProvider<Block, SignedBlock<Block>, AccountId, Block::Hash>>(
+				at,
+			)
+			.map_err(|e| map_err(e, "Failed to get char traits"))?
+			.unwrap_or(1);
+		let char_traits = if api_version < 2 {
+			// Runtime before community char traits
+			if community_id.is_some() {
+				return Err(map_err(
+					"Community char traits are not supported by runtime",
+					"Failed to get char traits",
+				)
+				.into())
+			}
+
+			#[allow(deprecated)]
+			api.get_char_traits_before_version_2(at, from_index, limit)
+		} else {
+			api.get_char_traits(at, from_index, limit, community_id)
+		}
+		.map_err(|e| map_err(e, "Failed to get char traits"))?;
 
 		Ok(char_traits)
 	}
+
+	fn get_phone_verifiers(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<PhoneVerifier<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let phone_verifiers = api
+			.get_phone_verifiers(at)
+			.map_err(|e| map_err(e, "Failed to get phone verifiers"))?;
+
+		Ok(phone_verifiers)
+	}
 }
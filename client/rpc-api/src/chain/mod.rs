@@ -2,7 +2,8 @@ pub mod client;
 mod error;
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use sp_rpc::{BlockchainStats, CharTrait, GenesisData};
+use sp_common::types::CommunityId;
+use sp_rpc::{BlockchainStats, CharTrait, GenesisData, PhoneVerifier};
 use sp_runtime::traits::Block as BlockT;
 
 #[rpc(client, server)]
@@ -19,12 +20,21 @@ pub trait ChainDataProviderApi<Block: BlockT, AccountId> {
 	#[method(name = "chain_getNetworkId")]
 	fn get_network_id(&self) -> RpcResult<String>;
 
-	/// RPC method provide current list of char traits
+	/// RPC method provide current list of char traits.
+	/// In case `community_id` is passed provide char traits of the community
 	#[method(name = "chain_getCharTraits")]
 	fn get_char_traits(
 		&self,
 		from_index: Option<u32>,
 		limit: Option<u32>,
 		at: Option<Block::Hash>,
+		community_id: Option<CommunityId>,
 	) -> RpcResult<Vec<CharTrait>>;
+
+	/// RPC method provides current list of phone verifiers with their metadata
+	#[method(name = "chain_getPhoneVerifiers")]
+	fn get_phone_verifiers(
+		&self,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<PhoneVerifier<AccountId>>>;
 }
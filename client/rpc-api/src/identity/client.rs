@@ -9,7 +9,7 @@ use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_common::{identity::AccountIdentity, types::CommunityId};
 use sp_core::hashing::blake2_512;
-use sp_rpc::{Contact, UserInfo};
+use sp_rpc::{CommunityTreasury, Contact, UserInfo};
 use sp_runtime::traits::Block as BlockT;
 use sp_std::fmt::Debug;
 use std::sync::Arc;
@@ -185,4 +185,21 @@ where
 			))
 		})?)
 	}
+
+	fn get_community_treasury(
+		&self,
+		community_id: CommunityId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<CommunityTreasury<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		Ok(api.get_community_treasury(at, community_id).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				0,
+				"Unable to query community treasury.",
+				Some(format!("{e:?}")),
+			))
+		})?)
+	}
 }
@@ -2,7 +2,7 @@ pub mod client;
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use sp_common::types::CommunityId;
-use sp_rpc::{Contact, UserInfo};
+use sp_rpc::{CommunityTreasury, Contact, UserInfo};
 
 #[rpc(client, server)]
 pub trait IdentityApi<BlockHash, AccountId, Username, PhoneNumber, PhoneNumberHash> {
@@ -72,4 +72,12 @@ pub trait IdentityApi<BlockHash, AccountId, Username, PhoneNumber, PhoneNumberHa
 	/// RPC method provides info about karma rewards period leaderboard
 	#[method(name = "community_getLeaderBoard")]
 	fn get_leader_board(&self, at: Option<BlockHash>) -> RpcResult<Vec<UserInfo<AccountId>>>;
+
+	/// RPC method provides community treasury account, its balance and appreciation bonus
+	#[method(name = "community_getTreasury")]
+	fn get_community_treasury(
+		&self,
+		community_id: CommunityId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<CommunityTreasury<AccountId>>>;
 }
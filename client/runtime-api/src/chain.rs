@@ -1,8 +1,10 @@
 use codec::Codec;
-use sp_rpc::{BlockchainStats, CharTrait, GenesisData};
+use sp_common::types::CommunityId;
+use sp_rpc::{BlockchainStats, CharTrait, GenesisData, PhoneVerifier};
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+	#[api_version(2)]
 	pub trait ChainDataProvider<SignedBlock, AccountId, Hash>
 	where
 		SignedBlock: Codec,
@@ -16,6 +18,18 @@ sp_api::decl_runtime_apis! {
 		fn get_genesis_data() -> GenesisData<AccountId>;
 
 		/// Provide list of char traits
+		#[changed_in(2)]
 		fn get_char_traits(from_index: Option<u32>, limit: Option<u32>) -> Vec<CharTrait>;
+
+		/// Provide list of char traits. In case `community_id` is passed provide
+		/// char traits attached to the community and its custom char traits
+		fn get_char_traits(
+			from_index: Option<u32>,
+			limit: Option<u32>,
+			community_id: Option<CommunityId>,
+		) -> Vec<CharTrait>;
+
+		/// Provide list of phone verifiers with their metadata
+		fn get_phone_verifiers() -> Vec<PhoneVerifier<AccountId>>;
 	}
 }
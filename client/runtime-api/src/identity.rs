@@ -1,7 +1,7 @@
 use codec::{Codec, MaxEncodedLen};
 use scale_info::prelude::vec::Vec;
 use sp_common::{identity::AccountIdentity, types::CommunityId};
-use sp_rpc::{Contact, UserInfo};
+use sp_rpc::{CommunityTreasury, Contact, UserInfo};
 use sp_std::fmt::Debug;
 
 sp_api::decl_runtime_apis! {
@@ -36,5 +36,8 @@ sp_api::decl_runtime_apis! {
 
 		// Get list of users that participate in karma reward
 		fn get_leader_board() -> Vec<UserInfo<AccountId>>;
+
+		/// Provide information about community treasury
+		fn get_community_treasury(community_id: CommunityId) -> Option<CommunityTreasury<AccountId>>;
 	}
 }
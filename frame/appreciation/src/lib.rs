@@ -2,21 +2,40 @@
 use frame_support::{
 	pallet_prelude::*,
 	traits::{Currency, ExistenceRequirement, Get},
-	BoundedVec,
+	BoundedVec, PalletId,
 };
+use frame_system::pallet_prelude::BlockNumberFor;
 use sp_common::{hooks::Hooks, identity::AccountIdentity, traits::IdentityProvider};
 
+pub mod migrations;
+mod traits;
 mod types;
 
-pub use crate::types::*;
+pub use crate::{traits::*, types::*};
 pub use pallet::*;
 use scale_info::prelude::string::String;
 use sp_common::{
-	traits::ScoreProvider,
+	traits::{CommunityMembershipProvider, ScoreProvider},
 	types::{CharTraitId, CommunityId, Score},
+	BoundedString,
 };
+use sp_runtime::traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero};
 use sp_std::vec::Vec;
 
+/// Ids of community char traits start from this value,
+/// so they never clash with ids of global char traits
+pub const FIRST_COMMUNITY_CHAR_TRAIT_ID: CharTraitId = 1 << 31;
+
+pub type CharTraitOf<T> = CharTrait<<T as Config>::CharNameLimit, <T as Config>::EmojiLimit>;
+
+pub type CommunityOf<T> = Community<
+	<T as Config>::CommunityNameLimit,
+	<T as Config>::CommunityDescriptionLimit,
+	<T as Config>::EmojiLimit,
+	<T as Config>::CommunityUrlLimit,
+	<T as Config>::MaxCharTrait,
+>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -46,6 +65,40 @@ pub mod pallet {
 		type EmojiLimit: Get<u32>;
 		/// Max length of `Community`'s urls
 		type CommunityUrlLimit: Get<u32>;
+		/// Number of blocks after which community invite or join request expires
+		type InviteExpiration: Get<BlockNumberFor<Self>>;
+		/// Number of blocks after which karma score used by `ScoreProvider` halves.
+		/// Zero disables decay, so karma score is equal to the lifetime `TraitScores`
+		type ScoreHalvingPeriod: Get<BlockNumberFor<Self>>;
+		/// Weigh payee's trait score increment for appreciation
+		type ScoreWeigher: ScoreWeigher<Self::Balance>;
+		/// Number of blocks during which appreciation back to the payer is
+		/// considered reciprocal
+		type ReciprocityWindow: Get<BlockNumberFor<Self>>;
+		/// Max number of appreciations from one payer to one payee during `RateLimitWindow`
+		type MaxAppreciationsPerPair: Get<u32>;
+		/// Max number of appreciations from one payer during `RateLimitWindow`
+		type MaxAppreciationsPerPayer: Get<u32>;
+		/// Number of blocks in appreciations rate limit window. Zero disables rate limits
+		type RateLimitWindow: Get<BlockNumberFor<Self>>;
+		/// Max length of appreciation memo in bytes
+		type MemoLimit: Get<u32>;
+		/// Max number of payees in `batch_appreciation`
+		type MaxBatchAppreciations: Get<u32>;
+		/// Max number of custom char traits in one community
+		type MaxCommunityCharTraits: Get<u32>;
+		/// The Appreciation's pallet id, used to derive community treasury accounts
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Ascending trait score thresholds. Crossing threshold `i` earns badge of level `i + 1`
+		type BadgeThresholds: Get<&'static [Score]>;
+		/// Number of blocks during which payer can undo appreciation. Zero disables undo
+		type AppreciationUndoPeriod: Get<BlockNumberFor<Self>>;
+		/// Number of blocks after which payer can take back funds of appreciation
+		/// to unregistered phone number
+		type EscrowExpiration: Get<BlockNumberFor<Self>>;
+		/// Max number of escrowed appreciations to one phone number
+		type MaxEscrowedAppreciations: Get<u32>;
 
 		type IdentityProvider: IdentityProvider<
 			Self::AccountId,
@@ -54,7 +107,11 @@ pub mod pallet {
 		>;
 	}
 
+	/// The current storage version
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::genesis_config]
@@ -92,14 +149,12 @@ pub mod pallet {
 	#[pallet::genesis_build]
 	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
 		fn build(&self) {
-			let bounded_char_traits: BoundedVec<
-				CharTrait<T::CharNameLimit, T::EmojiLimit>,
-				T::MaxCharTrait,
-			> = self
-				.char_traits
-				.clone()
-				.into_iter()
-				.map(|(id, name, emoji)| CharTrait {
+			assert!(
+				self.char_traits.len() <= T::MaxCharTrait::get() as usize,
+				"Initial number of char_traits should be lower than T::MaxCharTrait"
+			);
+			self.char_traits.clone().into_iter().for_each(|(id, name, emoji)| {
+				let char_trait = CharTrait {
 					id,
 					name: name.try_into().expect(
 						"Max length of character trait name should be lower than T::CharNameLimit",
@@ -107,32 +162,24 @@ pub mod pallet {
 					emoji: emoji.try_into().expect(
 						"Max length of character trait name should be lower than T::EmojiLimit",
 					),
-				})
-				.collect::<Vec<_>>()
-				.try_into()
-				.expect("Initial number of char_traits should be lower than T::MaxCharTrait");
-			CharTraits::<T>::put(bounded_char_traits);
+				};
+				CharTraits::<T>::insert(id, char_trait);
+			});
 
 			NoCharTraitId::<T>::put(self.no_char_trait_id);
 			SignupCharTraitId::<T>::put(self.signup_char_trait_id);
 			SpenderCharTraitId::<T>::put(self.spender_char_trait_id);
 			AmbassadorCharTraitId::<T>::put(self.ambassador_char_trait_id);
 
-			let bounded_communities: BoundedVec<
-				Community<
-					T::CommunityNameLimit,
-					T::CommunityDescriptionLimit,
-					T::EmojiLimit,
-					T::CommunityUrlLimit,
-					T::MaxCharTrait,
-				>,
-				T::MaxCommunities,
-			> = self
-				.communities
+			assert!(
+				self.communities.len() <= T::MaxCommunities::get() as usize,
+				"Initial number of communities should be lower than T::MaxCommunities"
+			);
+			self.communities
 				.clone()
 				.into_iter()
-				.map(|(id, name, desc, emoji, website_url, twitter_url, insta_url, face_url, discord_url, char_traits, closed)| {
-					Community {
+				.for_each(|(id, name, desc, emoji, website_url, twitter_url, insta_url, face_url, discord_url, char_traits, closed)| {
+					let community = Community {
 						id,
 						name: name.try_into().expect("Max length of community name should be lower than T::CommunityNameLimit"),
 						desc: desc.try_into().expect("Max length of community desc should be lower than T::CommunityDescriptionLimit"),
@@ -144,15 +191,13 @@ pub mod pallet {
 						discord_url: discord_url.try_into().expect("Max length of community discord url should be lower than T::CommunityUrlLimit"),
 						char_traits: char_traits.try_into().expect("Max length of community character traits should be lower that T::MaxCharTrait"),
 						closed,
-					}
-				})
-				.collect::<Vec<_>>()
-				.try_into()
-				.expect("Initial number of communities should be lower than T::MaxCommunities");
-			Communities::<T>::put(bounded_communities);
+					};
+					Communities::<T>::insert(id, community);
+				});
 
 			self.community_membership.iter().for_each(|(account_id, community_id, role)| {
 				CommunityMembership::<T>::insert(account_id, community_id, role);
+				CommunityAccounts::<T>::insert(community_id, account_id, ());
 			});
 
 			NoCommunityId::<T>::put(self.no_community_id);
@@ -187,33 +232,31 @@ pub mod pallet {
 	pub type AmbassadorCharTraitId<T: Config> =
 		StorageValue<_, CharTraitId, ResultQuery<Error<T>::NonExistentStorageValue>>;
 
+	/// Character traits by id. Number of traits is limited by `T::MaxCharTrait`
 	#[pallet::storage]
-	#[pallet::getter(fn char_traits)]
-	pub type CharTraits<T: Config> = StorageValue<
-		_,
-		BoundedVec<CharTrait<T::CharNameLimit, T::EmojiLimit>, T::MaxCharTrait>,
-		ValueQuery,
-	>;
+	pub type CharTraits<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, CharTraitId, CharTraitOf<T>, OptionQuery>;
 
 	#[pallet::storage]
 	pub type NoCommunityId<T: Config> =
 		StorageValue<_, CharTraitId, ResultQuery<Error<T>::NonExistentStorageValue>>;
 
+	/// Communities by id. Number of communities is limited by `T::MaxCommunities`
 	#[pallet::storage]
-	pub type Communities<T: Config> = StorageValue<
-		_,
-		BoundedVec<
-			Community<
-				T::CommunityNameLimit,
-				T::CommunityDescriptionLimit,
-				T::EmojiLimit,
-				T::CommunityUrlLimit,
-				T::MaxCharTrait,
-			>,
-			T::MaxCommunities,
-		>,
-		ValueQuery,
-	>;
+	pub type Communities<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, CommunityId, CommunityOf<T>, OptionQuery>;
+
+	/// Communities that were archived by root. Archived communities are kept in storage
+	/// but do not accept appreciations or metadata changes
+	#[pallet::storage]
+	pub type ArchivedCommunities<T: Config> =
+		StorageMap<_, Blake2_128Concat, CommunityId, (), OptionQuery>;
+
+	/// Communities which are being removed. Members, bans, invites and join requests
+	/// of such community are cleaned up by subsequent `remove_community` calls
+	#[pallet::storage]
+	pub type CommunityRemovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, CommunityId, CommunityRemoval, OptionQuery>;
 
 	#[pallet::storage]
 	pub type CommunityMembership<T: Config> = StorageDoubleMap<
@@ -226,6 +269,56 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Accounts banned from community. Banned account can't join the community
+	/// until it is unbanned by the community admin
+	#[pallet::storage]
+	pub type BannedMembers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CommunityId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Pending community invites with block number when invite expires
+	#[pallet::storage]
+	pub type PendingInvites<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CommunityId,
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// Pending requests to join community with block number when request expires
+	#[pallet::storage]
+	pub type JoinRequests<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CommunityId,
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// Accounts which are members of the community or have a ban, invite or join request
+	/// in it. Used to clean up the community entries on the community removal
+	#[pallet::storage]
+	pub type CommunityAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CommunityId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
 	#[pallet::storage]
 	pub type TraitScores<T: Config> = StorageNMap<
 		_,
@@ -238,8 +331,143 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Decaying trait score with the `ScoreHalvingPeriod` era it was updated at.
+	/// Score halves each era, lifetime score is kept in `TraitScores`
+	#[pallet::storage]
+	pub type DecayingTraitScores<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, CommunityId>,
+			NMapKey<Blake2_128Concat, CharTraitId>,
+		),
+		(Score, u32),
+		OptionQuery,
+	>;
+
+	/// `ScoreHalvingPeriod` era since which trait scores without `DecayingTraitScores`
+	/// record decay. Set to the era of the runtime upgrade which enabled decay, so
+	/// scores earned before it are not decayed retroactively
+	#[pallet::storage]
+	pub type DecayStartEra<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Badges earned by account for trait score in the community, with the block
+	/// number they were earned at
+	#[pallet::storage]
+	pub type Badges<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, CommunityId>,
+			NMapKey<Blake2_128Concat, CharTraitId>,
+			NMapKey<Blake2_128Concat, u8>,
+		),
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// The last appreciation from payer (first key) to payee (second key),
+	/// which can be undone during `AppreciationUndoPeriod`
+	#[pallet::storage]
+	pub type RevocableAppreciations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		RevocableAppreciation<T::Balance, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// Appreciations to unregistered phone number hash (first key) from payer (second key).
+	/// Released to the user on signup
+	#[pallet::storage]
+	pub type EscrowedAppreciations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PhoneNumberHash,
+		Blake2_128Concat,
+		T::AccountId,
+		EscrowedAppreciation<T::Balance, BlockNumberFor<T>, T::MemoLimit>,
+		OptionQuery,
+	>;
+
+	/// Index of the next escrowed appreciation
+	#[pallet::storage]
+	pub type NextEscrowIndex<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Phone number hashes (second key) with escrowed appreciation from payer (first key)
+	#[pallet::storage]
+	pub type PayerEscrows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::PhoneNumberHash,
+		(),
+		OptionQuery,
+	>;
+
+	/// Block number of the last appreciation from payer (first key) to payee (second key)
+	#[pallet::storage]
+	pub type LastAppreciationAt<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// Number of appreciations from payer (first key) to payee (second key)
+	/// with the start of `RateLimitWindow` they were counted in
+	#[pallet::storage]
+	pub type PairAppreciations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(BlockNumberFor<T>, u32),
+		OptionQuery,
+	>;
+
+	/// Number of appreciations from payer with the start of `RateLimitWindow`
+	/// they were counted in
+	#[pallet::storage]
+	pub type PayerAppreciations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (BlockNumberFor<T>, u32), OptionQuery>;
+
+	/// Char traits which can't be used in new appreciations. Scores of these traits are kept
+	#[pallet::storage]
+	pub type RetiredCharTraits<T: Config> = StorageMap<_, Blake2_128Concat, CharTraitId, ()>;
+
+	/// Custom char traits, which can be used only in the community they belong to
+	#[pallet::storage]
+	pub type CommunityCharTraits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CommunityId,
+		Blake2_128Concat,
+		CharTraitId,
+		CharTraitOf<T>,
+		OptionQuery,
+	>;
+
+	/// Index of the next community char trait, id is `FIRST_COMMUNITY_CHAR_TRAIT_ID + index`
+	#[pallet::storage]
+	pub type NextCommunityCharTraitIndex<T: Config> = StorageValue<_, CharTraitId, ValueQuery>;
+
+	/// Amount community treasury pays to the payee on each appreciation within the community
 	#[pallet::storage]
-	pub type Referral<T: Config> = StorageValue<_, bool, ValueQuery>;
+	pub type AppreciationBonus<T: Config> =
+		StorageMap<_, Blake2_128Concat, CommunityId, T::Balance, OptionQuery>;
+
+	/// Account (value) whose appreciation brought the new user (key) to the network.
+	/// Each user can be referred only once
+	#[pallet::storage]
+	pub type Referrers<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -256,6 +484,8 @@ pub mod pallet {
 			community_id: CommunityId,
 			/// Character trait
 			char_trait_id: CharTraitId,
+			/// Optional message attached to appreciation
+			memo: Option<BoundedString<T::MemoLimit>>,
 		},
 		/// May happens multiply times when `appreciation` tx happen and ones per `new_user` tx.
 		/// Happens even if transaction execution fails
@@ -284,6 +514,127 @@ pub mod pallet {
 			username: T::Username,
 			phone_number_hash: T::PhoneNumberHash,
 		},
+		/// Happens when `update_community` tx happen
+		CommunityUpdated { community_id: CommunityId },
+		/// Happens when `archive_community` tx happen
+		CommunityArchived { community_id: CommunityId },
+		/// Happens when `unarchive_community` tx happen
+		CommunityUnarchived { community_id: CommunityId },
+		/// Happens when `remove_community` tx happen
+		CommunityRemoved {
+			community_id: CommunityId,
+			/// Number of members whose membership was removed with the community
+			members: u32,
+		},
+		/// Happens when `leave_community` tx happen
+		CommunityMemberLeft { community_id: CommunityId, account_id: T::AccountId },
+		/// Happens when `remove_member` tx happen
+		CommunityMemberRemoved {
+			community_id: CommunityId,
+			/// Admin who removed the member
+			admin: T::AccountId,
+			account_id: T::AccountId,
+		},
+		/// Happens when `ban_member` tx happen
+		CommunityMemberBanned {
+			community_id: CommunityId,
+			/// Admin who banned the account
+			admin: T::AccountId,
+			account_id: T::AccountId,
+		},
+		/// Happens when `unban_member` tx happen
+		CommunityMemberUnbanned {
+			community_id: CommunityId,
+			/// Admin who unbanned the account
+			admin: T::AccountId,
+			account_id: T::AccountId,
+		},
+		/// Happens when `invite_member` tx happen
+		CommunityMemberInvited {
+			community_id: CommunityId,
+			/// Admin who invited the account
+			admin: T::AccountId,
+			account_id: T::AccountId,
+			/// Block number after which invite can't be accepted
+			expires_at: BlockNumberFor<T>,
+		},
+		/// Happens when `request_to_join` tx happen
+		CommunityJoinRequested {
+			community_id: CommunityId,
+			account_id: T::AccountId,
+			/// Block number after which request can't be approved
+			expires_at: BlockNumberFor<T>,
+		},
+		/// Happens when `accept_invite` or `approve_join_request` tx happen
+		CommunityMemberJoined { community_id: CommunityId, account_id: T::AccountId },
+		/// Happens when `set_role` tx happen
+		CommunityRoleChanged {
+			community_id: CommunityId,
+			/// Account who changed the role
+			by: T::AccountId,
+			account_id: T::AccountId,
+			role: CommunityRole,
+		},
+		/// Happens when `transfer_ownership` tx happen
+		CommunityOwnershipTransferred {
+			community_id: CommunityId,
+			old_owner: T::AccountId,
+			new_owner: T::AccountId,
+		},
+		/// Happens when `set_community_owner` tx happen
+		CommunityOwnerSet { community_id: CommunityId, owner: T::AccountId },
+		/// Happens when `attach_char_trait` tx happen
+		CommunityCharTraitAttached { community_id: CommunityId, char_trait_id: CharTraitId },
+		/// Happens when `detach_char_trait` tx happen
+		CommunityCharTraitDetached { community_id: CommunityId, char_trait_id: CharTraitId },
+		/// Happens when `retire_char_trait` tx happen
+		CharTraitRetired { char_trait_id: CharTraitId },
+		/// Happens when `add_community_char_trait` tx happen
+		CommunityCharTraitAdded { community_id: CommunityId, char_trait_id: CharTraitId },
+		/// Happens when `remove_community_char_trait` tx happen
+		CommunityCharTraitRemoved { community_id: CommunityId, char_trait_id: CharTraitId },
+		/// Happens when `donate_to_community` tx happen
+		CommunityDonation { community_id: CommunityId, who: T::AccountId, amount: T::Balance },
+		/// Happens when `spend_community_funds` tx happen
+		CommunityFundsSpent {
+			community_id: CommunityId,
+			admin: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+		},
+		/// Happens when `set_appreciation_bonus` tx happen
+		CommunityAppreciationBonusSet { community_id: CommunityId, bonus: Option<T::Balance> },
+		/// Happens when community treasury pays bonus for appreciation within the community
+		CommunityBonusPaid { community_id: CommunityId, payee: T::AccountId, amount: T::Balance },
+		/// Happens when trait score crosses one of `BadgeThresholds`
+		BadgeEarned {
+			who: T::AccountId,
+			community_id: CommunityId,
+			char_trait_id: CharTraitId,
+			level: u8,
+		},
+		/// Happens when appreciation to unregistered phone number is escrowed
+		AppreciationEscrowed {
+			payer: T::AccountId,
+			phone_number_hash: T::PhoneNumberHash,
+			amount: T::Balance,
+			community_id: CommunityId,
+			char_trait_id: CharTraitId,
+		},
+		/// Happens when escrowed appreciation funds returned to the payer
+		EscrowRefunded {
+			payer: T::AccountId,
+			phone_number_hash: T::PhoneNumberHash,
+			amount: T::Balance,
+		},
+		/// Happens when `undo_appreciation` tx happen
+		AppreciationRevoked {
+			payer: T::AccountId,
+			payee: T::AccountId,
+			amount: T::Balance,
+			community_id: CommunityId,
+			char_trait_id: CharTraitId,
+		},
 	}
 
 	#[pallet::error]
@@ -303,7 +654,7 @@ pub mod pallet {
 		/// Closed community - only community admin can invite new members
 		/// and only members can appreciate each other in the community
 		CommunityClosed,
-		///
+		/// Account doesn't have a community role required for this action
 		NotAdmin,
 		/// Try to add character trait with existed property
 		/// (same `id` or `name` or `emoji`)
@@ -315,12 +666,74 @@ pub mod pallet {
 		CommunityAlreadyExists,
 		/// No more communities can be added
 		CommunityLimitExceeded,
+		/// Community is archived and can't be used until it is unarchived
+		CommunityArchived,
+		/// Account is banned in the community
+		Banned,
+		/// Account is already banned in the community
+		AlreadyBanned,
+		/// Account is not banned in the community
+		NotBanned,
+		/// Account with the same or higher community role can't be removed,
+		/// banned, promoted or demoted
+		InsufficientRole,
+		/// Role can't be set with `set_role`. Use `transfer_ownership` to change
+		/// community owner and `remove_member` to remove account from community
+		InvalidRole,
+		/// Account is already a member of the community
+		AlreadyMember,
+		/// No invite to the community for this account
+		InviteNotFound,
+		/// Invite to the community expired
+		InviteExpired,
+		/// No request to join the community from this account
+		JoinRequestNotFound,
+		/// Request to join the community expired
+		JoinRequestExpired,
+		/// Request to join the community already exists and not expired
+		JoinRequestAlreadyExists,
+		/// Too many appreciations from the payer or to the same payee
+		/// during `RateLimitWindow`
+		RateLimitExceeded,
+		/// Memo is not a valid UTF-8 string
+		InvalidMemo,
+		/// Char trait is retired and can't be used in new appreciations
+		CharTraitRetired,
+		/// Char trait is already attached to the community
+		CharTraitAlreadyAttached,
+		/// Char trait id is reserved for community char traits
+		CharTraitIdReserved,
+		/// Char trait name or emoji is not a valid UTF-8 string
+		InvalidCharTrait,
+		/// There is no appreciation to the payee which can be undone
+		AppreciationNotRevocable,
+		/// `AppreciationUndoPeriod` passed since the appreciation
+		UndoPeriodExpired,
+		/// Payee already moved funds received with the appreciation
+		AppreciationFundsMoved,
+		/// Referral appreciation can't be undone, because referral reward is already issued
+		ReferralNotRevocable,
+		/// Payer already has escrowed appreciation to the phone number
+		EscrowAlreadyExists,
+		/// Too many escrowed appreciations to the phone number
+		TooManyEscrowedAppreciations,
+		/// There is no escrowed appreciation from the payer to the phone number
+		EscrowNotFound,
+		/// `EscrowExpiration` hasn't passed yet
+		EscrowNotExpired,
+		/// Escrowed amount is less than existential deposit
+		EscrowAmountTooLow,
+		/// Community owner can't leave the community. Use `transfer_ownership`
+		/// before leaving
+		OwnerCannotLeave,
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(
+			10_000 + T::DbWeight::get().writes(1).ref_time() + T::ScoreWeigher::weight()
+		)]
 		pub fn appreciation(
 			origin: OriginFor<T>,
 			to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
@@ -329,70 +742,45 @@ pub mod pallet {
 			char_trait_id: Option<CharTraitId>,
 		) -> DispatchResult {
 			let payer = ensure_signed(origin)?;
-			let payee = Self::get_account_id(to).ok_or(Error::<T>::NotFound)?;
-			let community_id = community_id.unwrap_or(NoCommunityId::<T>::get()?);
-			let char_trait_id = char_trait_id.unwrap_or(NoCharTraitId::<T>::get()?);
-			let referral = Referral::<T>::take();
-
-			let new_member =
-				Self::process_appreciation(&payer, &payee, community_id, char_trait_id, referral)?;
 
-			T::Currency::transfer(&payer, &payee, amount, ExistenceRequirement::KeepAlive)?;
-
-			T::Hooks::on_appreciation(
-				payer.clone(),
-				payee.clone(),
-				amount,
-				community_id,
-				char_trait_id,
-			)?;
-
-			if new_member {
-				Self::deposit_event(Event::<T>::NewCommunityMember {
-					community_id,
-					payer: payer.clone(),
-					payee: payee.clone(),
-				});
-			}
-
-			Self::deposit_event(Event::<T>::Appreciation {
-				payer,
-				payee,
-				amount,
-				community_id,
-				char_trait_id,
-			});
-
-			Ok(())
+			Self::do_appreciation(payer, to, amount, community_id, char_trait_id, None)
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
 		pub fn set_admin(
 			origin: OriginFor<T>,
 			community_id: CommunityId,
 			new_admin: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+
+			let community = Communities::<T>::get(community_id).ok_or(Error::<T>::NotFound)?;
 			ensure!(
-				matches!(
-					CommunityMembership::<T>::get(&who, community_id),
-					Some(CommunityRole::Admin)
-				),
-				Error::<T>::NotAdmin
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
 			);
-
-			let community = Communities::<T>::get()
-				.into_iter()
-				.find(|community| community.id == community_id)
-				.ok_or(Error::<T>::NotFound)?;
 			let new_admin_identity =
 				T::IdentityProvider::get_identity_info(&new_admin).ok_or(Error::<T>::NotFound)?;
+			ensure!(
+				!BannedMembers::<T>::contains_key(&new_admin_identity.account_id, community_id),
+				Error::<T>::Banned
+			);
+			// Owner can't be demoted to admin
+			ensure!(
+				!matches!(
+					CommunityMembership::<T>::get(&new_admin_identity.account_id, community_id),
+					Some(CommunityRole::Owner)
+				),
+				Error::<T>::InsufficientRole
+			);
 			CommunityMembership::<T>::insert(
 				&new_admin_identity.account_id,
 				community_id,
 				CommunityRole::Admin,
 			);
+			CommunityAccounts::<T>::insert(community_id, &new_admin_identity.account_id, ());
 
 			T::Hooks::on_set_admin(who, new_admin_identity.account_id.clone())?;
 
@@ -424,16 +812,17 @@ pub mod pallet {
 			ensure_root(origin)?;
 
 			ensure!(id != NoCharTraitId::<T>::get()?, Error::<T>::CharTraitAlreadyExists);
+			ensure!(id < FIRST_COMMUNITY_CHAR_TRAIT_ID, Error::<T>::CharTraitIdReserved);
 
-			let mut traits = CharTraits::<T>::get();
+			ensure!(!CharTraits::<T>::contains_key(id), Error::<T>::CharTraitAlreadyExists);
+			Self::ensure_valid_char_trait(&name, &emoji, CharTraits::<T>::iter_values())?;
 			ensure!(
-				!traits.iter().any(|t| t.id == id || t.name == name || t.emoji == emoji),
-				Error::<T>::CharTraitAlreadyExists
+				CharTraits::<T>::count() < T::MaxCharTrait::get(),
+				Error::<T>::CharTraitLimitExceeded
 			);
 
 			let char_trait = CharTrait { id, name, emoji };
-			traits.try_push(char_trait).map_err(|_| Error::<T>::CharTraitLimitExceeded)?;
-			CharTraits::<T>::put(traits);
+			CharTraits::<T>::insert(id, char_trait);
 
 			Ok(())
 		}
@@ -444,7 +833,7 @@ pub mod pallet {
 		/// Can only be called by Root origin (Sudo). Use Sudo::call to call
 		/// this transaction
 		#[pallet::call_index(3)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
 		pub fn add_community(
 			origin: OriginFor<T>,
 			id: CommunityId,
@@ -465,11 +854,16 @@ pub mod pallet {
 
 			ensure!(id != NoCommunityId::<T>::get()?, Error::<T>::CommunityAlreadyExists);
 
-			let mut communties = Communities::<T>::get();
 			ensure!(
-				!communties.iter().any(|c| c.id == id || c.name == name),
+				!Communities::<T>::contains_key(id) &&
+					!CommunityRemovals::<T>::contains_key(id) &&
+					!Communities::<T>::iter_values().any(|c| c.name == name),
 				Error::<T>::CommunityAlreadyExists
 			);
+			ensure!(
+				Communities::<T>::count() < T::MaxCommunities::get(),
+				Error::<T>::CommunityLimitExceeded
+			);
 
 			let community = Community {
 				id,
@@ -484,10 +878,10 @@ pub mod pallet {
 				char_traits,
 				closed,
 			};
-			communties.try_push(community).map_err(|_| Error::<T>::CommunityLimitExceeded)?;
-			Communities::<T>::put(communties);
+			Communities::<T>::insert(id, community);
 
-			CommunityMembership::<T>::insert(admin, id, CommunityRole::Admin);
+			CommunityMembership::<T>::insert(&admin, id, CommunityRole::Owner);
+			CommunityAccounts::<T>::insert(id, admin, ());
 
 			Ok(())
 		}
@@ -518,40 +912,1427 @@ pub mod pallet {
 
 			Ok(())
 		}
-	}
-}
 
-impl<T: pallet::Config> Pallet<T> {
-	fn get_account_id(
-		to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
-	) -> Option<T::AccountId> {
-		match to {
-			AccountIdentity::AccountId(account_id) => Some(account_id),
-			AccountIdentity::PhoneNumberHash(phone_number_hash) =>
-				T::IdentityProvider::identity_by_number(&phone_number_hash).map(|v| v.account_id),
-			AccountIdentity::Username(username) =>
-				T::IdentityProvider::identity_by_name(&username).map(|v| v.account_id),
-		}
-	}
+		/// Update community metadata. Only passed (`Some`) values are changed.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		pub fn update_community(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			desc: Option<BoundedString<T::CommunityDescriptionLimit>>,
+			emoji: Option<BoundedString<T::EmojiLimit>>,
+			website_url: Option<BoundedString<T::CommunityUrlLimit>>,
+			twitter_url: Option<BoundedString<T::CommunityUrlLimit>>,
+			insta_url: Option<BoundedString<T::CommunityUrlLimit>>,
+			face_url: Option<BoundedString<T::CommunityUrlLimit>>,
+			discord_url: Option<BoundedString<T::CommunityUrlLimit>>,
+			closed: Option<bool>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_edit_metadata)?;
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
 
-	pub fn set_referral_flag(flag: bool) {
-		Referral::<T>::put(flag);
-	}
+			Communities::<T>::try_mutate(community_id, |community| -> DispatchResult {
+				let community = community.as_mut().ok_or(Error::<T>::CommunityNotFound)?;
+
+				if let Some(desc) = desc {
+					community.desc = desc;
+				}
+				if let Some(emoji) = emoji {
+					community.emoji = emoji;
+				}
+				if let Some(website_url) = website_url {
+					community.website_url = website_url;
+				}
+				if let Some(twitter_url) = twitter_url {
+					community.twitter_url = twitter_url;
+				}
+				if let Some(insta_url) = insta_url {
+					community.insta_url = insta_url;
+				}
+				if let Some(face_url) = face_url {
+					community.face_url = face_url;
+				}
+				if let Some(discord_url) = discord_url {
+					community.discord_url = discord_url;
+				}
+				if let Some(closed) = closed {
+					community.closed = closed;
+				}
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::CommunityUpdated { community_id });
 
-	pub fn increment_trait_score(
-		account_id: &T::AccountId,
-		community_id: CommunityId,
-		char_trait_id: CharTraitId,
+			Ok(())
+		}
+
+		/// Archive community. Archived community keeps its members and trait scores,
+		/// but can't be used for appreciations or changed by admins.
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call
+		/// this transaction
+		#[pallet::call_index(6)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn archive_community(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(Self::is_community_exists(community_id)?, Error::<T>::CommunityNotFound);
+			ensure!(community_id != NoCommunityId::<T>::get()?, Error::<T>::CommunityNotFound);
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+
+			ArchivedCommunities::<T>::insert(community_id, ());
+
+			Self::deposit_event(Event::<T>::CommunityArchived { community_id });
+
+			Ok(())
+		}
+
+		/// Restore archived community.
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call
+		/// this transaction
+		#[pallet::call_index(7)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn unarchive_community(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(
+				ArchivedCommunities::<T>::take(community_id).is_some(),
+				Error::<T>::CommunityNotFound
+			);
+
+			Self::deposit_event(Event::<T>::CommunityUnarchived { community_id });
+
+			Ok(())
+		}
+
+		/// Remove community from storage with all its members and
+		/// trait scores earned in this community. Funds left on the community
+		/// treasury account are moved to the treasury.
+		///
+		/// The community is removed by the first call, while its members, bans, invites
+		/// and join requests are cleaned up for up to `limit` accounts per call.
+		/// The call should be repeated until `CommunityRemoved` event happens.
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call
+		/// this transaction
+		#[pallet::call_index(8)]
+		#[pallet::weight(Pallet::<T>::remove_community_weight(*limit))]
+		pub fn remove_community(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			limit: u32,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			let mut removal = match CommunityRemovals::<T>::get(community_id) {
+				Some(removal) => removal,
+				None => {
+					ensure!(
+						Communities::<T>::contains_key(community_id),
+						Error::<T>::CommunityNotFound
+					);
+					Communities::<T>::remove(community_id);
+					ArchivedCommunities::<T>::remove(community_id);
+					let _result =
+						CommunityCharTraits::<T>::clear_prefix(community_id, u32::MAX, None);
+					AppreciationBonus::<T>::remove(community_id);
+					Self::sweep_community_funds(community_id)?;
+
+					CommunityRemoval::default()
+				},
+			};
+
+			// Collect first to do not get undefined behavior from storage while
+			// simultaneously drain and remove
+			let accounts: Vec<_> = CommunityAccounts::<T>::drain_prefix(community_id)
+				.take(limit as usize)
+				.map(|(account_id, _)| account_id)
+				.collect();
+			// Bans, invites and join requests make no sense without the community
+			accounts.iter().for_each(|account_id| {
+				if CommunityMembership::<T>::contains_key(account_id, community_id) {
+					Self::remove_membership(account_id, community_id);
+					removal.members = removal.members.saturating_add(1);
+				}
+				BannedMembers::<T>::remove(account_id, community_id);
+				PendingInvites::<T>::remove(account_id, community_id);
+				JoinRequests::<T>::remove(account_id, community_id);
+			});
+
+			if (accounts.len() as u32) < limit {
+				CommunityRemovals::<T>::remove(community_id);
+				Self::deposit_event(Event::<T>::CommunityRemoved {
+					community_id,
+					members: removal.members,
+				});
+
+				return Ok(())
+			}
+
+			CommunityRemovals::<T>::insert(community_id, removal);
+
+			Ok(())
+		}
+
+		/// Leave community. Trait scores earned in this community are removed.
+		#[pallet::call_index(9)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 3).ref_time())]
+		pub fn leave_community(origin: OriginFor<T>, community_id: CommunityId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let role =
+				CommunityMembership::<T>::get(&who, community_id).ok_or(Error::<T>::NotMember)?;
+			ensure!(!matches!(role, CommunityRole::Owner), Error::<T>::OwnerCannotLeave);
+
+			Self::remove_membership(&who, community_id);
+
+			Self::deposit_event(Event::<T>::CommunityMemberLeft { community_id, account_id: who });
+
+			Ok(())
+		}
+
+		/// Remove member from community. Trait scores earned in this community are removed.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(10)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(7, 3).ref_time())]
+		pub fn remove_member(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			member: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let role = Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+			let member = Self::get_account_id(member).ok_or(Error::<T>::NotFound)?;
+
+			let member_role = CommunityMembership::<T>::get(&member, community_id)
+				.ok_or(Error::<T>::NotMember)?;
+			ensure!(role.can_manage(&member_role), Error::<T>::InsufficientRole);
+			Self::remove_membership(&member, community_id);
+
+			Self::deposit_event(Event::<T>::CommunityMemberRemoved {
+				community_id,
+				admin: who,
+				account_id: member,
+			});
+
+			Ok(())
+		}
+
+		/// Ban account in community. If account is a member of the community it is
+		/// removed from the community. Banned account can't join the community again
+		/// by appreciation until it is unbanned.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(11)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 4).ref_time())]
+		pub fn ban_member(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			member: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let role = Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+			let member = Self::get_account_id(member).ok_or(Error::<T>::NotFound)?;
+			ensure!(
+				!BannedMembers::<T>::contains_key(&member, community_id),
+				Error::<T>::AlreadyBanned
+			);
+
+			if let Some(member_role) = CommunityMembership::<T>::get(&member, community_id) {
+				ensure!(role.can_manage(&member_role), Error::<T>::InsufficientRole);
+				Self::remove_membership(&member, community_id);
+			}
+			PendingInvites::<T>::remove(&member, community_id);
+			JoinRequests::<T>::remove(&member, community_id);
+			BannedMembers::<T>::insert(&member, community_id, ());
+			CommunityAccounts::<T>::insert(community_id, &member, ());
+
+			Self::deposit_event(Event::<T>::CommunityMemberBanned {
+				community_id,
+				admin: who,
+				account_id: member,
+			});
+
+			Ok(())
+		}
+
+		/// Remove account from community ban list
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(12)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 2).ref_time())]
+		pub fn unban_member(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			member: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+			let member = Self::get_account_id(member).ok_or(Error::<T>::NotFound)?;
+			ensure!(
+				BannedMembers::<T>::take(&member, community_id).is_some(),
+				Error::<T>::NotBanned
+			);
+			Self::release_community_account(&member, community_id);
+
+			Self::deposit_event(Event::<T>::CommunityMemberUnbanned {
+				community_id,
+				admin: who,
+				account_id: member,
+			});
+
+			Ok(())
+		}
+
+		/// Invite account to community. Invite should be accepted with `accept_invite`
+		/// before it expires. Repeated invite renews expiration.
+		///
+		/// Can only be called by moderator, admin or owner of the community
+		#[pallet::call_index(13)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 2).ref_time())]
+		pub fn invite_member(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			member: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_invite)?;
+			let member = Self::get_account_id(member).ok_or(Error::<T>::NotFound)?;
+			Self::ensure_can_join(&member, community_id)?;
+
+			let expires_at = frame_system::Pallet::<T>::block_number() + T::InviteExpiration::get();
+			PendingInvites::<T>::insert(&member, community_id, expires_at);
+			CommunityAccounts::<T>::insert(community_id, &member, ());
+
+			Self::deposit_event(Event::<T>::CommunityMemberInvited {
+				community_id,
+				admin: who,
+				account_id: member,
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		/// Accept not expired invite to community
+		#[pallet::call_index(14)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 4).ref_time())]
+		pub fn accept_invite(origin: OriginFor<T>, community_id: CommunityId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let expires_at =
+				PendingInvites::<T>::get(&who, community_id).ok_or(Error::<T>::InviteNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= expires_at,
+				Error::<T>::InviteExpired
+			);
+			Self::ensure_can_join(&who, community_id)?;
+
+			Self::join_community(who, community_id);
+
+			Ok(())
+		}
+
+		/// Request to join community. Request should be approved by community admin
+		/// with `approve_join_request` before it expires.
+		#[pallet::call_index(15)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 2).ref_time())]
+		pub fn request_to_join(origin: OriginFor<T>, community_id: CommunityId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_can_join(&who, community_id)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				JoinRequests::<T>::get(&who, community_id)
+					.map_or(true, |expires_at| now > expires_at),
+				Error::<T>::JoinRequestAlreadyExists
+			);
+
+			let expires_at = now + T::InviteExpiration::get();
+			JoinRequests::<T>::insert(&who, community_id, expires_at);
+			CommunityAccounts::<T>::insert(community_id, &who, ());
+
+			Self::deposit_event(Event::<T>::CommunityJoinRequested {
+				community_id,
+				account_id: who,
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		/// Approve not expired request to join community
+		///
+		/// Can only be called by moderator, admin or owner of the community
+		#[pallet::call_index(16)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 4).ref_time())]
+		pub fn approve_join_request(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			member: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_invite)?;
+			let member = Self::get_account_id(member).ok_or(Error::<T>::NotFound)?;
+			let expires_at = JoinRequests::<T>::get(&member, community_id)
+				.ok_or(Error::<T>::JoinRequestNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= expires_at,
+				Error::<T>::JoinRequestExpired
+			);
+			Self::ensure_can_join(&member, community_id)?;
+
+			Self::join_community(member, community_id);
+
+			Ok(())
+		}
+
+		/// Change community role of the member. Caller can change role only of members
+		/// with lower role and can't grant role higher than its own.
+		///
+		/// Can only be called by admin or owner of the community
+		#[pallet::call_index(17)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		pub fn set_role(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			member: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+			role: CommunityRole,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let caller_role = Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+			ensure!(
+				!matches!(role, CommunityRole::Owner | CommunityRole::None),
+				Error::<T>::InvalidRole
+			);
+			let member = Self::get_account_id(member).ok_or(Error::<T>::NotFound)?;
+
+			let member_role = CommunityMembership::<T>::get(&member, community_id)
+				.ok_or(Error::<T>::NotMember)?;
+			ensure!(
+				caller_role.can_manage(&member_role) && caller_role.rank() >= role.rank(),
+				Error::<T>::InsufficientRole
+			);
+
+			CommunityMembership::<T>::insert(&member, community_id, role);
+
+			Self::deposit_event(Event::<T>::CommunityRoleChanged {
+				community_id,
+				by: who,
+				account_id: member,
+				role,
+			});
+
+			Ok(())
+		}
+
+		/// Transfer community ownership to other member. Previous owner becomes admin.
+		///
+		/// Can only be called by owner of the community
+		#[pallet::call_index(18)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2).ref_time())]
+		pub fn transfer_ownership(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			new_owner: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, |role| matches!(role, CommunityRole::Owner))?;
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+			let new_owner = Self::get_account_id(new_owner).ok_or(Error::<T>::NotFound)?;
+			ensure!(
+				CommunityMembership::<T>::contains_key(&new_owner, community_id),
+				Error::<T>::NotMember
+			);
+
+			CommunityMembership::<T>::insert(&who, community_id, CommunityRole::Admin);
+			CommunityMembership::<T>::insert(&new_owner, community_id, CommunityRole::Owner);
+
+			Self::deposit_event(Event::<T>::CommunityOwnershipTransferred {
+				community_id,
+				old_owner: who,
+				new_owner,
+			});
+
+			Ok(())
+		}
+
+		/// Same as `appreciation`, but allows to attach an optional UTF-8 `memo`
+		/// which is stored in `Appreciation` event.
+		///
+		/// Zero `amount` makes kudos-only appreciation without tokens transfer
+		#[pallet::call_index(19)]
+		#[pallet::weight(
+			10_000 + T::DbWeight::get().writes(1).ref_time() + T::ScoreWeigher::weight()
+		)]
+		pub fn appreciation_with_memo(
+			origin: OriginFor<T>,
+			to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+			amount: T::Balance,
+			community_id: Option<CommunityId>,
+			char_trait_id: Option<CharTraitId>,
+			memo: Option<BoundedString<T::MemoLimit>>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			if let Some(memo) = &memo {
+				ensure!(String::try_from(memo.clone()).is_ok(), Error::<T>::InvalidMemo);
+			}
+
+			Self::do_appreciation(payer, to, amount, community_id, char_trait_id, memo)
+		}
+
+		/// Appreciate multiple payees within one community in a single transaction.
+		/// Each entry is `(payee, amount, char_trait_id)` and processed the same way as
+		/// separate `appreciation`. Fails entirely if any entry fails
+		#[pallet::call_index(20)]
+		#[pallet::weight(
+			(10_000 + T::DbWeight::get().writes(1).ref_time() + T::ScoreWeigher::weight())
+				.saturating_mul(appreciations.len() as u64)
+		)]
+		pub fn batch_appreciation(
+			origin: OriginFor<T>,
+			community_id: Option<CommunityId>,
+			appreciations: BoundedVec<
+				(
+					AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+					T::Balance,
+					Option<CharTraitId>,
+				),
+				T::MaxBatchAppreciations,
+			>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+
+			appreciations.into_iter().try_for_each(|(to, amount, char_trait_id)| {
+				Self::do_appreciation(payer.clone(), to, amount, community_id, char_trait_id, None)
+			})
+		}
+
+		/// Attach existing global char trait to the community, so it can be used
+		/// in community appreciations.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(21)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 1).ref_time())]
+		pub fn attach_char_trait(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			char_trait_id: CharTraitId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_edit_metadata)?;
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+			ensure!(CharTraits::<T>::contains_key(char_trait_id), Error::<T>::CharTraitNotFound);
+			ensure!(
+				!RetiredCharTraits::<T>::contains_key(char_trait_id),
+				Error::<T>::CharTraitRetired
+			);
+
+			Communities::<T>::try_mutate(community_id, |community| -> DispatchResult {
+				let community = community.as_mut().ok_or(Error::<T>::CommunityNotFound)?;
+				ensure!(
+					!community.char_traits.contains(&char_trait_id),
+					Error::<T>::CharTraitAlreadyAttached
+				);
+				community
+					.char_traits
+					.try_push(char_trait_id)
+					.map_err(|_| Error::<T>::CharTraitLimitExceeded)?;
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::CommunityCharTraitAttached {
+				community_id,
+				char_trait_id,
+			});
+
+			Ok(())
+		}
+
+		/// Detach char trait from the community. Members keep their scores of this trait.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(22)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		pub fn detach_char_trait(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			char_trait_id: CharTraitId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_edit_metadata)?;
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+
+			Communities::<T>::try_mutate(community_id, |community| -> DispatchResult {
+				let community = community.as_mut().ok_or(Error::<T>::CommunityNotFound)?;
+				let len = community.char_traits.len();
+				community.char_traits.retain(|id| *id != char_trait_id);
+				ensure!(community.char_traits.len() < len, Error::<T>::CharTraitNotFound);
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::CommunityCharTraitDetached {
+				community_id,
+				char_trait_id,
+			});
+
+			Ok(())
+		}
+
+		/// Retire global char trait. Retired trait can't be used in new appreciations,
+		/// but scores of this trait are kept.
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call
+		/// this transaction
+		#[pallet::call_index(23)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn retire_char_trait(
+			origin: OriginFor<T>,
+			char_trait_id: CharTraitId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(CharTraits::<T>::contains_key(char_trait_id), Error::<T>::CharTraitNotFound);
+			ensure!(
+				!RetiredCharTraits::<T>::contains_key(char_trait_id),
+				Error::<T>::CharTraitRetired
+			);
+
+			RetiredCharTraits::<T>::insert(char_trait_id, ());
+
+			Self::deposit_event(Event::<T>::CharTraitRetired { char_trait_id });
+
+			Ok(())
+		}
+
+		/// Add custom char trait, which can be used only in this community.
+		/// Fails if char trait with same name or emoji exists globally
+		/// or in the community. Id of the char trait is assigned automatically.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(24)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 2).ref_time())]
+		pub fn add_community_char_trait(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			name: BoundedString<T::CharNameLimit>,
+			emoji: BoundedString<T::EmojiLimit>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_edit_metadata)?;
+			ensure!(Communities::<T>::contains_key(community_id), Error::<T>::CommunityNotFound);
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+
+			Self::ensure_valid_char_trait(
+				&name,
+				&emoji,
+				CharTraits::<T>::iter_values()
+					.chain(CommunityCharTraits::<T>::iter_prefix_values(community_id)),
+			)?;
+			ensure!(
+				(CommunityCharTraits::<T>::iter_key_prefix(community_id).count() as u32) <
+					T::MaxCommunityCharTraits::get(),
+				Error::<T>::CharTraitLimitExceeded
+			);
+
+			let index = NextCommunityCharTraitIndex::<T>::get();
+			let id = FIRST_COMMUNITY_CHAR_TRAIT_ID
+				.checked_add(index)
+				.ok_or(Error::<T>::CharTraitLimitExceeded)?;
+			NextCommunityCharTraitIndex::<T>::put(index + 1);
+
+			let char_trait = CharTrait { id, name, emoji };
+			CommunityCharTraits::<T>::insert(community_id, id, char_trait);
+
+			Self::deposit_event(Event::<T>::CommunityCharTraitAdded {
+				community_id,
+				char_trait_id: id,
+			});
+
+			Ok(())
+		}
+
+		/// Remove custom char trait of the community. Members keep their scores of this trait.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(25)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		pub fn remove_community_char_trait(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			char_trait_id: CharTraitId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::can_edit_metadata)?;
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+			ensure!(
+				CommunityCharTraits::<T>::contains_key(community_id, char_trait_id),
+				Error::<T>::CharTraitNotFound
+			);
+
+			CommunityCharTraits::<T>::remove(community_id, char_trait_id);
+
+			Self::deposit_event(Event::<T>::CommunityCharTraitRemoved {
+				community_id,
+				char_trait_id,
+			});
+
+			Ok(())
+		}
+
+		/// Transfer `amount` of tokens to the community treasury
+		#[pallet::call_index(26)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2).ref_time())]
+		pub fn donate_to_community(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Communities::<T>::contains_key(community_id), Error::<T>::CommunityNotFound);
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+
+			T::Currency::transfer(
+				&who,
+				&Self::community_account_id(community_id),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			Self::deposit_event(Event::<T>::CommunityDonation { community_id, who, amount });
+
+			Ok(())
+		}
+
+		/// Transfer `amount` of tokens from the community treasury to `to` account
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(27)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 2).ref_time())]
+		pub fn spend_community_funds(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+			let to = Self::get_account_id(to).ok_or(Error::<T>::NotFound)?;
+
+			T::Currency::transfer(
+				&Self::community_account_id(community_id),
+				&to,
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			Self::deposit_event(Event::<T>::CommunityFundsSpent {
+				community_id,
+				admin: who,
+				to,
+				amount,
+			});
+
+			Ok(())
+		}
+
+		/// Set amount community treasury pays to the payee on each appreciation
+		/// within the community. `None` or zero disables bonus.
+		///
+		/// Can only be called by admin of the community
+		#[pallet::call_index(28)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		pub fn set_appreciation_bonus(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			bonus: Option<T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_role(&who, community_id, CommunityRole::is_admin)?;
+			ensure!(
+				!ArchivedCommunities::<T>::contains_key(community_id),
+				Error::<T>::CommunityArchived
+			);
+
+			let bonus = bonus.filter(|bonus| !bonus.is_zero());
+			AppreciationBonus::<T>::set(community_id, bonus);
+
+			Self::deposit_event(Event::<T>::CommunityAppreciationBonusSet { community_id, bonus });
+
+			Ok(())
+		}
+
+		/// Undo the last appreciation to the payee. Transferred tokens are returned to the
+		/// payer, community bonus is returned to the community treasury and trait scores
+		/// granted by the appreciation are taken back.
+		///
+		/// Can only be called by the payer during `AppreciationUndoPeriod` and only if
+		/// payee has not moved the funds. Referral appreciation can't be undone
+		#[pallet::call_index(29)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(12, 10).ref_time())]
+		pub fn undo_appreciation(
+			origin: OriginFor<T>,
+			to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			let payee = Self::get_account_id(to).ok_or(Error::<T>::NotFound)?;
+
+			let appreciation = RevocableAppreciations::<T>::get(&payer, &payee)
+				.ok_or(Error::<T>::AppreciationNotRevocable)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now.saturating_sub(appreciation.appreciated_at) <= T::AppreciationUndoPeriod::get(),
+				Error::<T>::UndoPeriodExpired
+			);
+			ensure!(
+				T::Currency::free_balance(&payee) >= appreciation.payee_balance,
+				Error::<T>::AppreciationFundsMoved
+			);
+			ensure!(!appreciation.referral, Error::<T>::ReferralNotRevocable);
+			RevocableAppreciations::<T>::remove(&payer, &payee);
+
+			if !appreciation.amount.is_zero() {
+				T::Currency::transfer(
+					&payee,
+					&payer,
+					appreciation.amount,
+					ExistenceRequirement::AllowDeath,
+				)?;
+			}
+			if !appreciation.bonus.is_zero() {
+				// Bonus of the removed community goes to the treasury with the rest of its funds
+				let bonus_account = if Communities::<T>::contains_key(appreciation.community_id) {
+					Self::community_account_id(appreciation.community_id)
+				} else {
+					<T as pallet_identity::Config>::Treasury::get().into_account_truncating()
+				};
+				T::Currency::transfer(
+					&payee,
+					&bonus_account,
+					appreciation.bonus,
+					ExistenceRequirement::AllowDeath,
+				)?;
+			}
+
+			Self::revoke_trait_scores(&payer, &payee, &appreciation)?;
+
+			T::Hooks::on_undo_appreciation(
+				payer.clone(),
+				payee.clone(),
+				appreciation.amount,
+				appreciation.community_id,
+				appreciation.char_trait_id,
+			)?;
+
+			Self::deposit_event(Event::<T>::AppreciationRevoked {
+				payer,
+				payee,
+				amount: appreciation.amount,
+				community_id: appreciation.community_id,
+				char_trait_id: appreciation.char_trait_id,
+			});
+
+			Ok(())
+		}
+
+		/// Take back funds of appreciation to unregistered phone number.
+		///
+		/// Can only be called by the payer after `EscrowExpiration`
+		#[pallet::call_index(30)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3).ref_time())]
+		pub fn refund_escrowed_appreciation(
+			origin: OriginFor<T>,
+			phone_number_hash: T::PhoneNumberHash,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+
+			let escrow = EscrowedAppreciations::<T>::get(&phone_number_hash, &payer)
+				.ok_or(Error::<T>::EscrowNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > escrow.expires_at,
+				Error::<T>::EscrowNotExpired
+			);
+
+			EscrowedAppreciations::<T>::remove(&phone_number_hash, &payer);
+			PayerEscrows::<T>::remove(&payer, &phone_number_hash);
+			Self::refund_escrow(payer, phone_number_hash, escrow.amount)
+		}
+
+		/// Set owner of the community. Used for communities created before
+		/// ownership was introduced. If `current_owner` is provided it becomes admin.
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call
+		/// this transaction
+		#[pallet::call_index(31)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2).ref_time())]
+		pub fn set_community_owner(
+			origin: OriginFor<T>,
+			community_id: CommunityId,
+			new_owner: T::AccountId,
+			current_owner: Option<T::AccountId>,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(Communities::<T>::contains_key(community_id), Error::<T>::CommunityNotFound);
+			ensure!(
+				CommunityMembership::<T>::contains_key(&new_owner, community_id),
+				Error::<T>::NotMember
+			);
+			if let Some(current_owner) = current_owner.as_ref() {
+				ensure!(
+					matches!(
+						CommunityMembership::<T>::get(current_owner, community_id),
+						Some(CommunityRole::Owner)
+					),
+					Error::<T>::InsufficientRole
+				);
+				CommunityMembership::<T>::insert(current_owner, community_id, CommunityRole::Admin);
+			}
+			CommunityMembership::<T>::insert(&new_owner, community_id, CommunityRole::Owner);
+
+			Self::deposit_event(Event::<T>::CommunityOwnerSet { community_id, owner: new_owner });
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: pallet::Config> Pallet<T> {
+	fn get_account_id(
+		to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+	) -> Option<T::AccountId> {
+		match to {
+			AccountIdentity::AccountId(account_id) => Some(account_id),
+			AccountIdentity::PhoneNumberHash(phone_number_hash) =>
+				T::IdentityProvider::identity_by_number(&phone_number_hash).map(|v| v.account_id),
+			AccountIdentity::Username(username) =>
+				T::IdentityProvider::identity_by_name(&username).map(|v| v.account_id),
+		}
+	}
+
+	fn do_appreciation(
+		payer: T::AccountId,
+		to: AccountIdentity<T::AccountId, T::Username, T::PhoneNumberHash>,
+		amount: T::Balance,
+		community_id: Option<CommunityId>,
+		char_trait_id: Option<CharTraitId>,
+		memo: Option<BoundedString<T::MemoLimit>>,
+	) -> DispatchResult {
+		let community_id = community_id.unwrap_or(NoCommunityId::<T>::get()?);
+		let char_trait_id = char_trait_id.unwrap_or(NoCharTraitId::<T>::get()?);
+		let payee = match Self::get_account_id(to.clone()) {
+			Some(payee) => payee,
+			// Funds to unregistered phone number are kept until the user signs up
+			None => match to {
+				AccountIdentity::PhoneNumberHash(phone_number_hash) =>
+					return Self::escrow_appreciation(
+						payer,
+						phone_number_hash,
+						amount,
+						community_id,
+						char_trait_id,
+						memo,
+					),
+				_ => return Err(Error::<T>::NotFound.into()),
+			},
+		};
+		let is_new_user = pallet_identity::Pallet::<T>::is_just_registered(&payee);
+		let referral = Self::note_referral(&payer, &payee, is_new_user);
+
+		Self::note_rate_limit(&payer, &payee)?;
+
+		Self::appreciate(payer, payee, amount, community_id, char_trait_id, memo, referral, false)
+	}
+
+	/// Appreciate already resolved payee. If `escrowed` funds are paid from the escrow account
+	#[allow(clippy::too_many_arguments)]
+	fn appreciate(
+		payer: T::AccountId,
+		payee: T::AccountId,
+		amount: T::Balance,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+		memo: Option<BoundedString<T::MemoLimit>>,
+		referral: bool,
+		escrowed: bool,
+	) -> DispatchResult {
+		let payee_score_key = (&payee, community_id, char_trait_id);
+		let payee_score_before = TraitScores::<T>::get(payee_score_key).unwrap_or_default();
+
+		let new_member = Self::process_appreciation(
+			&payer,
+			&payee,
+			amount,
+			community_id,
+			char_trait_id,
+			referral,
+		)?;
+
+		// Kudos-only appreciation doesn't transfer tokens
+		if escrowed && !amount.is_zero() {
+			T::Currency::transfer(
+				&Self::escrow_account_id(),
+				&payee,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		} else if !amount.is_zero() {
+			T::Currency::transfer(&payer, &payee, amount, ExistenceRequirement::KeepAlive)?;
+		}
+
+		let bonus = Self::pay_appreciation_bonus(amount, community_id, char_trait_id, &payee)?;
+
+		if !T::AppreciationUndoPeriod::get().is_zero() {
+			let payee_score = TraitScores::<T>::get(payee_score_key)
+				.unwrap_or_default()
+				.saturating_sub(payee_score_before);
+			RevocableAppreciations::<T>::insert(
+				&payer,
+				&payee,
+				RevocableAppreciation {
+					amount,
+					community_id,
+					char_trait_id,
+					payee_score,
+					referral,
+					new_member,
+					bonus,
+					payee_balance: T::Currency::free_balance(&payee),
+					appreciated_at: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+		}
+
+		T::Hooks::on_appreciation(
+			payer.clone(),
+			payee.clone(),
+			amount,
+			community_id,
+			char_trait_id,
+		)?;
+
+		if new_member {
+			Self::deposit_event(Event::<T>::NewCommunityMember {
+				community_id,
+				payer: payer.clone(),
+				payee: payee.clone(),
+			});
+		}
+
+		Self::deposit_event(Event::<T>::Appreciation {
+			payer,
+			payee,
+			amount,
+			community_id,
+			char_trait_id,
+			memo,
+		});
+
+		Ok(())
+	}
+
+	/// Account which keeps funds of appreciations to unregistered phone numbers
+	pub fn escrow_account_id() -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(b"escrow")
+	}
+
+	fn escrow_appreciation(
+		payer: T::AccountId,
+		phone_number_hash: T::PhoneNumberHash,
+		amount: T::Balance,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+		memo: Option<BoundedString<T::MemoLimit>>,
+	) -> DispatchResult {
+		// Kudos-only escrows are not allowed, so they can't fill the escrow limit
+		ensure!(amount >= T::Currency::minimum_balance(), Error::<T>::EscrowAmountTooLow);
+		ensure!(
+			!EscrowedAppreciations::<T>::contains_key(&phone_number_hash, &payer),
+			Error::<T>::EscrowAlreadyExists
+		);
+		ensure!(
+			(EscrowedAppreciations::<T>::iter_key_prefix(&phone_number_hash).count() as u32) <
+				T::MaxEscrowedAppreciations::get(),
+			Error::<T>::TooManyEscrowedAppreciations
+		);
+		// Payee isn't known until signup, while one escrow per payer and phone number
+		// already bounds appreciations of the pair
+		Self::note_payer_rate_limit(&payer)?;
+
+		T::Currency::transfer(
+			&payer,
+			&Self::escrow_account_id(),
+			amount,
+			ExistenceRequirement::KeepAlive,
+		)?;
+
+		let expires_at =
+			frame_system::Pallet::<T>::block_number().saturating_add(T::EscrowExpiration::get());
+		let index = NextEscrowIndex::<T>::mutate(|index| {
+			*index += 1;
+			*index - 1
+		});
+		EscrowedAppreciations::<T>::insert(
+			&phone_number_hash,
+			&payer,
+			EscrowedAppreciation { amount, community_id, char_trait_id, memo, expires_at, index },
+		);
+		PayerEscrows::<T>::insert(&payer, &phone_number_hash, ());
+
+		Self::deposit_event(Event::<T>::AppreciationEscrowed {
+			payer,
+			phone_number_hash,
+			amount,
+			community_id,
+			char_trait_id,
+		});
+
+		Ok(())
+	}
+
+	/// Complete all escrowed appreciations to the phone number of just signed up user.
+	/// The first of them counts as a referral. Appreciation which can't be completed
+	/// anymore (e.g. community was archived) is refunded to the payer. If even refund
+	/// fails escrow is kept, so it never fails the signup
+	fn release_escrowed_appreciations(
+		payee: &T::AccountId,
+		phone_number_hash: &T::PhoneNumberHash,
+	) -> DispatchResult {
+		let mut escrows: Vec<_> =
+			EscrowedAppreciations::<T>::drain_prefix(phone_number_hash).collect();
+		// Storage order depends on payer key hash, so sort by insertion order
+		escrows.sort_by_key(|(_, escrow)| escrow.index);
+		for (payer, escrow) in escrows {
+			PayerEscrows::<T>::remove(&payer, phone_number_hash);
+
+			let released = frame_support::storage::with_storage_layer(|| {
+				let referral = Self::note_referral(&payer, payee, true);
+				Self::appreciate(
+					payer.clone(),
+					payee.clone(),
+					escrow.amount,
+					escrow.community_id,
+					escrow.char_trait_id,
+					escrow.memo.clone(),
+					referral,
+					true,
+				)
+			})
+			.is_ok();
+			if released {
+				continue
+			}
+
+			let refunded = frame_support::storage::with_storage_layer(|| {
+				Self::refund_escrow(payer.clone(), phone_number_hash.clone(), escrow.amount)
+			})
+			.is_ok();
+			if !refunded {
+				EscrowedAppreciations::<T>::insert(phone_number_hash, &payer, escrow);
+				PayerEscrows::<T>::insert(&payer, phone_number_hash, ());
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Remove escrowed appreciations of deleted payer. Funds go to the treasury,
+	/// the same way as balance of deleted account
+	fn remove_payer_escrows(payer: &T::AccountId) -> DispatchResult {
+		let treasury = <T as pallet_identity::Config>::Treasury::get().into_account_truncating();
+		let phone_number_hashes: Vec<_> = PayerEscrows::<T>::drain_prefix(payer).collect();
+		for (phone_number_hash, _) in phone_number_hashes {
+			let escrow = match EscrowedAppreciations::<T>::take(&phone_number_hash, payer) {
+				Some(escrow) => escrow,
+				None => continue,
+			};
+
+			if !escrow.amount.is_zero() {
+				T::Currency::transfer(
+					&Self::escrow_account_id(),
+					&treasury,
+					escrow.amount,
+					ExistenceRequirement::AllowDeath,
+				)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn refund_escrow(
+		payer: T::AccountId,
+		phone_number_hash: T::PhoneNumberHash,
+		amount: T::Balance,
+	) -> DispatchResult {
+		if !amount.is_zero() {
+			T::Currency::transfer(
+				&Self::escrow_account_id(),
+				&payer,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		}
+
+		Self::deposit_event(Event::<T>::EscrowRefunded { payer, phone_number_hash, amount });
+
+		Ok(())
+	}
+
+	/// Account of the community treasury
+	pub fn community_account_id(community_id: CommunityId) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(community_id)
+	}
+
+	/// Weight of `remove_community` call which cleans up to `limit` community accounts
+	fn remove_community_weight(limit: u32) -> u64 {
+		let max_community_char_traits = T::MaxCommunityCharTraits::get() as u64;
+		// Each account can be a member with trait scores, decaying trait scores and
+		// badges for every global and community char trait, a ban, an invite and a join request
+		let max_char_traits = T::MaxCharTrait::get() as u64 + max_community_char_traits;
+		let account_ops = 5 + 3 * max_char_traits;
+		let account = T::DbWeight::get().reads_writes(account_ops, account_ops);
+
+		10_000 +
+			T::DbWeight::get()
+				.reads_writes(6, 8 + max_community_char_traits)
+				.saturating_add(account.saturating_mul(limit as u64))
+				.ref_time()
+	}
+
+	/// Move all funds of the community treasury account to the treasury
+	fn sweep_community_funds(community_id: CommunityId) -> DispatchResult {
+		let community_account = Self::community_account_id(community_id);
+		let funds = T::Currency::free_balance(&community_account);
+		if !funds.is_zero() {
+			T::Currency::transfer(
+				&community_account,
+				&<T as pallet_identity::Config>::Treasury::get().into_account_truncating(),
+				funds,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// Pay appreciation bonus to the `payee` from the community treasury.
+	/// Nothing happens if appreciation is not within a community, it is kudos-only,
+	/// bonus isn't set or treasury doesn't have enough funds
+	///
+	/// # Returns
+	/// Paid bonus, zero if it wasn't paid
+	fn pay_appreciation_bonus(
+		amount: T::Balance,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+		payee: &T::AccountId,
+	) -> Result<T::Balance, DispatchError> {
+		let is_payment = NoCharTraitId::<T>::get()? == char_trait_id;
+		// Kudos are free, so treasury could be drained by repeated kudos-only appreciations
+		if is_payment || amount.is_zero() || NoCommunityId::<T>::get()? == community_id {
+			return Ok(Zero::zero())
+		}
+
+		let bonus = match AppreciationBonus::<T>::get(community_id) {
+			Some(bonus) => bonus,
+			None => return Ok(Zero::zero()),
+		};
+
+		let paid = T::Currency::transfer(
+			&Self::community_account_id(community_id),
+			payee,
+			bonus,
+			ExistenceRequirement::KeepAlive,
+		)
+		.is_ok();
+
+		if !paid {
+			return Ok(Zero::zero())
+		}
+
+		Self::deposit_event(Event::<T>::CommunityBonusPaid {
+			community_id,
+			payee: payee.clone(),
+			amount: bonus,
+		});
+
+		Ok(bonus)
+	}
+
+	/// Appreciation is a referral if it brought the new user to the network, either
+	/// waiting for `new_user` tx in the same block or escrowed till signup.
+	/// Only the first such appreciation counts. Return `true` if the appreciation is a referral
+	fn note_referral(payer: &T::AccountId, payee: &T::AccountId, is_new_user: bool) -> bool {
+		if !is_new_user || Referrers::<T>::contains_key(payee) {
+			return false
+		}
+
+		Referrers::<T>::insert(payee, payer);
+		true
+	}
+
+	pub fn increment_trait_score(
+		account_id: &T::AccountId,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+	) {
+		Self::add_trait_score(account_id, community_id, char_trait_id, 1)
+	}
+
+	/// Increase trait score by `score`. Nothing happens if `score` is zero
+	pub fn add_trait_score(
+		account_id: &T::AccountId,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+		score: Score,
 	) {
-		TraitScores::<T>::mutate((account_id, community_id, char_trait_id), |value| {
-			*value = Some(value.unwrap_or_default() + 1)
+		if score.is_zero() {
+			return
+		}
+
+		let key = (account_id, community_id, char_trait_id);
+		// Decayed score falls back to `TraitScores`, so it should be read before the update
+		if let Some(era) = Self::current_score_era() {
+			let decayed_score = Self::decayed_trait_score(key, era);
+			DecayingTraitScores::<T>::insert(key, (decayed_score.saturating_add(score), era));
+		}
+
+		let (old_score, new_score) = TraitScores::<T>::mutate(key, |value| {
+			let old_score = value.unwrap_or_default();
+			let new_score = old_score.saturating_add(score);
+			*value = Some(new_score);
+			(old_score, new_score)
 		});
 
 		Self::deposit_event(Event::<T>::CharTraitScoreIncreased {
 			who: account_id.clone(),
 			community_id,
 			char_trait_id,
-		})
+		});
+
+		Self::award_badges(account_id, community_id, char_trait_id, old_score, new_score);
+	}
+
+	/// Decrease trait score by `score`. Nothing happens if `score` is zero
+	fn sub_trait_score(
+		account_id: &T::AccountId,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+		score: Score,
+	) {
+		if score.is_zero() {
+			return
+		}
+
+		let key = (account_id, community_id, char_trait_id);
+		// Decayed score falls back to `TraitScores`, so it should be read before the update
+		if let Some(era) = Self::current_score_era() {
+			let decayed_score = Self::decayed_trait_score(key, era);
+			DecayingTraitScores::<T>::insert(key, (decayed_score.saturating_sub(score), era));
+		}
+
+		TraitScores::<T>::mutate(key, |value| {
+			*value = value.map(|value| value.saturating_sub(score))
+		});
+	}
+
+	/// Take back trait scores granted by `process_appreciation` for the `appreciation`.
+	/// Earned badges and community membership are kept
+	fn revoke_trait_scores(
+		payer: &T::AccountId,
+		payee: &T::AccountId,
+		appreciation: &RevocableAppreciation<T::Balance, BlockNumberFor<T>>,
+	) -> DispatchResult {
+		let community_id = appreciation.community_id;
+		// Payments don't change trait scores
+		if NoCharTraitId::<T>::get()? == appreciation.char_trait_id {
+			return Ok(())
+		}
+
+		if appreciation.new_member {
+			Self::sub_trait_score(payer, community_id, AmbassadorCharTraitId::<T>::get()?, 1);
+		}
+		Self::sub_trait_score(payer, community_id, SpenderCharTraitId::<T>::get()?, 1);
+		Self::sub_trait_score(
+			payee,
+			community_id,
+			appreciation.char_trait_id,
+			appreciation.payee_score,
+		);
+
+		Ok(())
+	}
+
+	/// Record badges for all thresholds crossed by trait score changed
+	/// from `old_score` to `new_score`. Each badge is earned only once
+	fn award_badges(
+		account_id: &T::AccountId,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+		old_score: Score,
+		new_score: Score,
+	) {
+		T::BadgeThresholds::get()
+			.iter()
+			.enumerate()
+			.filter(|(_, threshold)| old_score < **threshold && **threshold <= new_score)
+			.for_each(|(index, _)| {
+				let level = (index + 1) as u8;
+				let key = (account_id, community_id, char_trait_id, level);
+				if Badges::<T>::contains_key(key) {
+					return
+				}
+
+				Badges::<T>::insert(key, frame_system::Pallet::<T>::block_number());
+				Self::deposit_event(Event::<T>::BadgeEarned {
+					who: account_id.clone(),
+					community_id,
+					char_trait_id,
+					level,
+				});
+			});
 	}
 
 	/// # Returns
@@ -561,6 +2342,7 @@ impl<T: pallet::Config> Pallet<T> {
 	pub fn process_appreciation(
 		payer: &T::AccountId,
 		payee: &T::AccountId,
+		amount: T::Balance,
 		community_id: CommunityId,
 		char_trait_id: CharTraitId,
 		referral: bool,
@@ -569,6 +2351,10 @@ impl<T: pallet::Config> Pallet<T> {
 			return Ok(false)
 		}
 
+		ensure!(!RetiredCharTraits::<T>::contains_key(char_trait_id), Error::<T>::CharTraitRetired);
+
+		let payee_score = Self::weigh_appreciation(payer, payee, amount);
+
 		// TODO: whether to check `char_trait_id` for existence?
 		if referral {
 			// Give payer karma points for helping to grow the network
@@ -583,17 +2369,24 @@ impl<T: pallet::Config> Pallet<T> {
 
 		// Standard appreciation w/o a community context
 		if NoCommunityId::<T>::get()? == community_id {
+			// Community char traits can't be used outside of community
+			ensure!(char_trait_id < FIRST_COMMUNITY_CHAR_TRAIT_ID, Error::<T>::CharTraitNotFound);
 			Self::increment_trait_score(payer, community_id, SpenderCharTraitId::<T>::get()?);
-			Self::increment_trait_score(payee, community_id, char_trait_id);
+			Self::add_trait_score(payee, community_id, char_trait_id, payee_score);
 			return Ok(false)
 		}
 
-		let community = Communities::<T>::get()
-			.into_iter()
-			.find(|v| v.id == community_id)
-			.ok_or(Error::<T>::CommunityNotFound)?;
+		let community = Communities::<T>::get(community_id).ok_or(Error::<T>::CommunityNotFound)?;
 
-		ensure!(community.char_traits.contains(&char_trait_id), Error::<T>::CharTraitNotFound,);
+		ensure!(
+			!ArchivedCommunities::<T>::contains_key(community_id),
+			Error::<T>::CommunityArchived
+		);
+		ensure!(
+			community.char_traits.contains(&char_trait_id) ||
+				CommunityCharTraits::<T>::contains_key(community_id, char_trait_id),
+			Error::<T>::CharTraitNotFound
+		);
 
 		let is_community_closed = community.closed;
 
@@ -604,40 +2397,112 @@ impl<T: pallet::Config> Pallet<T> {
 
 		let new_member = match (payer_membership, payee_membership) {
 			(CommunityRole::None, _) => return Err(Error::<T>::NotMember.into()),
-			(_, CommunityRole::Admin) | (_, CommunityRole::Member) => {
-				Self::increment_trait_score(payer, community_id, SpenderCharTraitId::<T>::get()?);
-				Self::increment_trait_score(payee, community_id, char_trait_id);
-				false
-			},
-			(CommunityRole::Admin, CommunityRole::None) => {
+			(_, CommunityRole::None) if BannedMembers::<T>::contains_key(payee, community_id) =>
+				return Err(Error::<T>::Banned.into()),
+			(payer_role, CommunityRole::None)
+				if payer_role.can_appreciate_into_closed() || !is_community_closed =>
+			{
 				Self::increment_trait_score(payer, community_id, SpenderCharTraitId::<T>::get()?);
 				Self::increment_trait_score(
 					payer,
 					community_id,
 					AmbassadorCharTraitId::<T>::get()?,
 				);
-				Self::increment_trait_score(payee, community_id, char_trait_id);
+				Self::add_trait_score(payee, community_id, char_trait_id, payee_score);
 				CommunityMembership::<T>::insert(payee, community_id, CommunityRole::Member);
+				CommunityAccounts::<T>::insert(community_id, payee, ());
 				true
 			},
-			(CommunityRole::Member, CommunityRole::None) if !is_community_closed => {
+			(_, CommunityRole::None) => return Err(Error::<T>::CommunityClosed.into()),
+			_ => {
 				Self::increment_trait_score(payer, community_id, SpenderCharTraitId::<T>::get()?);
-				Self::increment_trait_score(
-					payer,
-					community_id,
-					AmbassadorCharTraitId::<T>::get()?,
-				);
-				Self::increment_trait_score(payee, community_id, char_trait_id);
-				CommunityMembership::<T>::insert(payee, community_id, CommunityRole::Member);
-				true
+				Self::add_trait_score(payee, community_id, char_trait_id, payee_score);
+				false
 			},
-			(CommunityRole::Member, CommunityRole::None) =>
-				return Err(Error::<T>::CommunityClosed.into()),
 		};
 
 		Ok(new_member)
 	}
 
+	/// Start of the current `RateLimitWindow`, `None` if rate limits are disabled
+	fn current_rate_limit_window() -> Option<BlockNumberFor<T>> {
+		let window = T::RateLimitWindow::get();
+		if window.is_zero() {
+			return None
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		Some(now - now % window)
+	}
+
+	/// Number of appreciations counted in the window started at `window_start`
+	fn count_in_window(
+		counter: Option<(BlockNumberFor<T>, u32)>,
+		window_start: BlockNumberFor<T>,
+	) -> u32 {
+		counter
+			.filter(|(start, _)| *start == window_start)
+			.map_or(0, |(_, count)| count)
+	}
+
+	/// Check whether appreciation from `payer` to `payee` exceeds rate limits
+	pub fn is_rate_limited(payer: &T::AccountId, payee: &T::AccountId) -> bool {
+		let window_start = match Self::current_rate_limit_window() {
+			Some(window_start) => window_start,
+			None => return false,
+		};
+
+		let pair_count =
+			Self::count_in_window(PairAppreciations::<T>::get(payer, payee), window_start);
+		let payer_count = Self::count_in_window(PayerAppreciations::<T>::get(payer), window_start);
+
+		pair_count >= T::MaxAppreciationsPerPair::get() ||
+			payer_count >= T::MaxAppreciationsPerPayer::get()
+	}
+
+	/// Count appreciation in the current rate limit window
+	fn note_rate_limit(payer: &T::AccountId, payee: &T::AccountId) -> DispatchResult {
+		let window_start = match Self::current_rate_limit_window() {
+			Some(window_start) => window_start,
+			None => return Ok(()),
+		};
+		ensure!(!Self::is_rate_limited(payer, payee), Error::<T>::RateLimitExceeded);
+
+		let pair_count =
+			Self::count_in_window(PairAppreciations::<T>::get(payer, payee), window_start);
+		PairAppreciations::<T>::insert(payer, payee, (window_start, pair_count + 1));
+
+		Self::note_payer_rate_limit(payer)
+	}
+
+	/// Count appreciation of the `payer` in the current rate limit window
+	fn note_payer_rate_limit(payer: &T::AccountId) -> DispatchResult {
+		let window_start = match Self::current_rate_limit_window() {
+			Some(window_start) => window_start,
+			None => return Ok(()),
+		};
+
+		let payer_count = Self::count_in_window(PayerAppreciations::<T>::get(payer), window_start);
+		ensure!(payer_count < T::MaxAppreciationsPerPayer::get(), Error::<T>::RateLimitExceeded);
+		PayerAppreciations::<T>::insert(payer, (window_start, payer_count + 1));
+
+		Ok(())
+	}
+
+	/// Weigh payee's trait score increment and remember the appreciation
+	/// to detect reciprocal appreciations
+	fn weigh_appreciation(payer: &T::AccountId, payee: &T::AccountId, amount: T::Balance) -> Score {
+		let now = frame_system::Pallet::<T>::block_number();
+		let reciprocal = LastAppreciationAt::<T>::get(payee, payer)
+			.map_or(false, |at| now.saturating_sub(at) <= T::ReciprocityWindow::get());
+		LastAppreciationAt::<T>::insert(payer, payee, now);
+
+		let payer_score = || Self::score_of(payer);
+		let context = AppreciationContext::new(amount, reciprocal, &payer_score);
+
+		T::ScoreWeigher::weigh(&context)
+	}
+
 	pub fn trait_scores_of(
 		account_id: &T::AccountId,
 	) -> scale_info::prelude::vec::Vec<(CommunityId, CharTraitId, Score)> {
@@ -652,6 +2517,56 @@ impl<T: pallet::Config> Pallet<T> {
 			.collect()
 	}
 
+	pub fn badges_of(
+		account_id: &T::AccountId,
+	) -> Vec<(CommunityId, CharTraitId, u8, BlockNumberFor<T>)> {
+		Badges::<T>::iter_prefix((account_id,))
+			.map(|((community_id, char_trait_id, level), earned_at)| {
+				(community_id, char_trait_id, level, earned_at)
+			})
+			.collect()
+	}
+
+	/// Same as `trait_scores_of` but with scores decayed according to `ScoreHalvingPeriod`
+	pub fn decayed_trait_scores_of(
+		account_id: &T::AccountId,
+	) -> scale_info::prelude::vec::Vec<(CommunityId, CharTraitId, Score)> {
+		let scores = Self::trait_scores_of(account_id);
+		let era = match Self::current_score_era() {
+			Some(era) => era,
+			None => return scores,
+		};
+
+		scores
+			.into_iter()
+			.map(|(community_id, char_trait_id, _)| {
+				let score =
+					Self::decayed_trait_score((account_id, community_id, char_trait_id), era);
+				(community_id, char_trait_id, score)
+			})
+			.collect()
+	}
+
+	/// Number of `ScoreHalvingPeriod` eras since genesis, `None` if decay is disabled
+	pub(crate) fn current_score_era() -> Option<u32> {
+		let period = T::ScoreHalvingPeriod::get();
+		if period.is_zero() {
+			return None
+		}
+
+		Some((frame_system::Pallet::<T>::block_number() / period).saturated_into())
+	}
+
+	/// Decayed score at the `era`. Scores without decaying record (earned before decay was
+	/// enabled) are treated as updated at `DecayStartEra`
+	fn decayed_trait_score(key: (&T::AccountId, CommunityId, CharTraitId), era: u32) -> Score {
+		let (score, updated_at) = DecayingTraitScores::<T>::get(key).unwrap_or_else(|| {
+			(TraitScores::<T>::get(key).unwrap_or_default(), DecayStartEra::<T>::get())
+		});
+
+		score.checked_shr(era.saturating_sub(updated_at)).unwrap_or_default()
+	}
+
 	pub fn community_membership_of(
 		account_id: &T::AccountId,
 	) -> scale_info::prelude::vec::Vec<(CommunityId, Score, bool)> {
@@ -667,14 +2582,97 @@ impl<T: pallet::Config> Pallet<T> {
 			.collect()
 	}
 
+	/// Check that char trait `name` and `emoji` are valid UTF-8 strings
+	/// which are not used by any of `existing` char traits
+	fn ensure_valid_char_trait(
+		name: &BoundedString<T::CharNameLimit>,
+		emoji: &BoundedString<T::EmojiLimit>,
+		mut existing: impl Iterator<Item = CharTraitOf<T>>,
+	) -> DispatchResult {
+		ensure!(
+			String::try_from(name.clone()).is_ok() && String::try_from(emoji.clone()).is_ok(),
+			Error::<T>::InvalidCharTrait
+		);
+		ensure!(
+			!existing.any(|t| t.name == *name || t.emoji == *emoji),
+			Error::<T>::CharTraitAlreadyExists
+		);
+
+		Ok(())
+	}
+
+	/// Check that account's community role has the permission and return this role
+	fn ensure_role(
+		account_id: &T::AccountId,
+		community_id: CommunityId,
+		permission: impl Fn(&CommunityRole) -> bool,
+	) -> Result<CommunityRole, DispatchError> {
+		let role = CommunityMembership::<T>::get(account_id, community_id).unwrap_or_default();
+		ensure!(permission(&role), Error::<T>::NotAdmin);
+
+		Ok(role)
+	}
+
+	/// Check that account can become a member of existing and not archived community
+	fn ensure_can_join(account_id: &T::AccountId, community_id: CommunityId) -> DispatchResult {
+		ensure!(
+			community_id != NoCommunityId::<T>::get()? &&
+				Communities::<T>::contains_key(community_id),
+			Error::<T>::CommunityNotFound
+		);
+		ensure!(
+			!ArchivedCommunities::<T>::contains_key(community_id),
+			Error::<T>::CommunityArchived
+		);
+		ensure!(
+			!CommunityMembership::<T>::contains_key(account_id, community_id),
+			Error::<T>::AlreadyMember
+		);
+		ensure!(!BannedMembers::<T>::contains_key(account_id, community_id), Error::<T>::Banned);
+
+		Ok(())
+	}
+
+	/// Add account to community as a member and remove its pending invite and join request
+	fn join_community(account_id: T::AccountId, community_id: CommunityId) {
+		PendingInvites::<T>::remove(&account_id, community_id);
+		JoinRequests::<T>::remove(&account_id, community_id);
+		CommunityMembership::<T>::insert(&account_id, community_id, CommunityRole::Member);
+		CommunityAccounts::<T>::insert(community_id, &account_id, ());
+
+		Self::deposit_event(Event::<T>::CommunityMemberJoined { community_id, account_id });
+	}
+
+	/// Remove account from community together with trait scores earned in this community
+	pub(crate) fn remove_membership(account_id: &T::AccountId, community_id: CommunityId) {
+		CommunityMembership::<T>::remove(account_id, community_id);
+		let _result = TraitScores::<T>::clear_prefix((account_id, community_id), u32::MAX, None);
+		let _result =
+			DecayingTraitScores::<T>::clear_prefix((account_id, community_id), u32::MAX, None);
+		let _result = Badges::<T>::clear_prefix((account_id, community_id), u32::MAX, None);
+		Self::release_community_account(account_id, community_id);
+	}
+
+	/// Remove account from `CommunityAccounts` of the community if it has no membership,
+	/// ban, invite or join request left there
+	fn release_community_account(account_id: &T::AccountId, community_id: CommunityId) {
+		if !CommunityMembership::<T>::contains_key(account_id, community_id) &&
+			!BannedMembers::<T>::contains_key(account_id, community_id) &&
+			!PendingInvites::<T>::contains_key(account_id, community_id) &&
+			!JoinRequests::<T>::contains_key(account_id, community_id)
+		{
+			CommunityAccounts::<T>::remove(community_id, account_id);
+		}
+	}
+
 	fn is_char_trait_exists(char_trait_id: CharTraitId) -> Result<bool, DispatchError> {
 		Ok(NoCharTraitId::<T>::get()? == char_trait_id ||
-			CharTraits::<T>::get().iter().any(|t| t.id == char_trait_id))
+			CharTraits::<T>::contains_key(char_trait_id))
 	}
 
 	fn is_community_exists(community_id: CommunityId) -> Result<bool, DispatchError> {
 		Ok(NoCommunityId::<T>::get()? == community_id ||
-			Communities::<T>::get().iter().any(|c| c.id == community_id))
+			Communities::<T>::contains_key(community_id))
 	}
 }
 
@@ -683,14 +2681,14 @@ impl<T: Config> Hooks<T::AccountId, T::Balance, T::Username, T::PhoneNumberHash>
 		_verifier: T::AccountId,
 		account_id: T::AccountId,
 		_name: T::Username,
-		_phone_number: T::PhoneNumberHash,
+		phone_number: T::PhoneNumberHash,
 	) -> DispatchResult {
 		let no_community_id = NoCommunityId::<T>::get()?;
 		let signup_char_trait_id = SignupCharTraitId::<T>::get()?;
 
 		Self::increment_trait_score(&account_id, no_community_id, signup_char_trait_id);
 
-		Ok(())
+		Self::release_escrowed_appreciations(&account_id, &phone_number)
 	}
 
 	fn on_update_user(
@@ -711,6 +2709,76 @@ impl<T: Config> Hooks<T::AccountId, T::Balance, T::Username, T::PhoneNumberHash>
 				CommunityMembership::<T>::insert(&new_account_id, community_id, community_role);
 			});
 
+			// Migrate user bans, so account change can't be used to escape the ban
+			let bans: Vec<_> = BannedMembers::<T>::drain_prefix(&old_account_id).collect();
+			bans.iter().for_each(|(community_id, _)| {
+				BannedMembers::<T>::insert(&new_account_id, community_id, ());
+			});
+
+			// Migrate pending invites and join requests
+			let invites: Vec<_> = PendingInvites::<T>::drain_prefix(&old_account_id).collect();
+			invites.iter().for_each(|(community_id, expires_at)| {
+				PendingInvites::<T>::insert(&new_account_id, community_id, expires_at);
+			});
+			let requests: Vec<_> = JoinRequests::<T>::drain_prefix(&old_account_id).collect();
+			requests.iter().for_each(|(community_id, expires_at)| {
+				JoinRequests::<T>::insert(&new_account_id, community_id, expires_at);
+			});
+
+			communities_membership
+				.iter()
+				.map(|(community_id, _)| community_id)
+				.chain(bans.iter().map(|(community_id, _)| community_id))
+				.chain(invites.iter().map(|(community_id, _)| community_id))
+				.chain(requests.iter().map(|(community_id, _)| community_id))
+				.for_each(|community_id| {
+					CommunityAccounts::<T>::remove(community_id, &old_account_id);
+					CommunityAccounts::<T>::insert(community_id, &new_account_id, ());
+				});
+
+			// Migrate sent appreciations, so reciprocity can't be escaped with account change
+			let appreciations: Vec<_> =
+				LastAppreciationAt::<T>::drain_prefix(&old_account_id).collect();
+			appreciations.iter().for_each(|(payee, at)| {
+				LastAppreciationAt::<T>::insert(&new_account_id, payee, at);
+			});
+
+			// Migrate rate limit counters, so limits can't be escaped with account change
+			let counters: Vec<_> = PairAppreciations::<T>::drain_prefix(&old_account_id).collect();
+			counters.iter().for_each(|(payee, counter)| {
+				PairAppreciations::<T>::insert(&new_account_id, payee, counter);
+			});
+			if let Some(counter) = PayerAppreciations::<T>::take(&old_account_id) {
+				PayerAppreciations::<T>::insert(&new_account_id, counter);
+			}
+			if let Some(referrer) = Referrers::<T>::take(&old_account_id) {
+				Referrers::<T>::insert(&new_account_id, referrer);
+			}
+			// Migrate escrowed appreciations, so new account can take the funds back
+			let escrows: Vec<_> = PayerEscrows::<T>::drain_prefix(&old_account_id).collect();
+			escrows.iter().for_each(|(phone_number_hash, _)| {
+				if let Some(escrow) =
+					EscrowedAppreciations::<T>::take(phone_number_hash, &old_account_id)
+				{
+					EscrowedAppreciations::<T>::insert(phone_number_hash, &new_account_id, escrow);
+					PayerEscrows::<T>::insert(&new_account_id, phone_number_hash, ());
+				}
+			});
+			let revocable: Vec<_> =
+				RevocableAppreciations::<T>::drain_prefix(&old_account_id).collect();
+			revocable.iter().for_each(|(payee, appreciation)| {
+				RevocableAppreciations::<T>::insert(&new_account_id, payee, appreciation);
+			});
+
+			// Migrate earned badges
+			let badges: Vec<_> = Badges::<T>::drain_prefix((&old_account_id,)).collect();
+			badges.iter().for_each(|((community_id, char_trait_id, level), earned_at)| {
+				Badges::<T>::insert(
+					(&new_account_id, community_id, char_trait_id, level),
+					earned_at,
+				);
+			});
+
 			// Migrate user trait score
 			let no_community_id = NoCommunityId::<T>::get()?;
 			communities_membership
@@ -727,7 +2795,16 @@ impl<T: Config> Hooks<T::AccountId, T::Balance, T::Username, T::PhoneNumberHash>
 							(&new_account_id, community_id, char_trait_id),
 							score,
 						);
-					})
+					});
+					let decaying_scores: Vec<_> =
+						DecayingTraitScores::<T>::drain_prefix((&old_account_id, community_id))
+							.collect();
+					decaying_scores.iter().for_each(|(char_trait_id, score)| {
+						DecayingTraitScores::<T>::insert(
+							(&new_account_id, community_id, char_trait_id),
+							score,
+						);
+					});
 				});
 		}
 
@@ -750,13 +2827,48 @@ impl<T: Config> Hooks<T::AccountId, T::Balance, T::Username, T::PhoneNumberHash>
 			.for_each(|community_id| {
 				let _result =
 					TraitScores::<T>::clear_prefix((&account_id, community_id), u32::MAX, None);
+				let _result = DecayingTraitScores::<T>::clear_prefix(
+					(&account_id, community_id),
+					u32::MAX,
+					None,
+				);
 			});
-		Ok(())
+		// Bans are kept, so banned member can't rejoin by registering the account again
+		let invites: Vec<_> = PendingInvites::<T>::drain_prefix(&account_id).collect();
+		let requests: Vec<_> = JoinRequests::<T>::drain_prefix(&account_id).collect();
+		communities_membership
+			.iter()
+			.map(|(community_id, _)| community_id)
+			.chain(invites.iter().map(|(community_id, _)| community_id))
+			.chain(requests.iter().map(|(community_id, _)| community_id))
+			.for_each(|community_id| Self::release_community_account(&account_id, *community_id));
+		let _result = LastAppreciationAt::<T>::clear_prefix(&account_id, u32::MAX, None);
+		let _result = PairAppreciations::<T>::clear_prefix(&account_id, u32::MAX, None);
+		PayerAppreciations::<T>::remove(&account_id);
+		let _result = Badges::<T>::clear_prefix((&account_id,), u32::MAX, None);
+		let _result = RevocableAppreciations::<T>::clear_prefix(&account_id, u32::MAX, None);
+		Referrers::<T>::remove(&account_id);
+
+		Self::remove_payer_escrows(&account_id)
 	}
 }
 
 impl<T: Config> ScoreProvider<T::AccountId> for Pallet<T> {
 	fn score_of(account_id: &T::AccountId) -> Score {
-		Self::trait_scores_of(account_id).iter().map(|(_, _, score)| score).sum()
+		Self::decayed_trait_scores_of(account_id)
+			.iter()
+			.map(|(_, _, score)| score)
+			.sum()
+	}
+}
+
+impl<T: Config> CommunityMembershipProvider<T::AccountId> for Pallet<T> {
+	fn share_community(who: &T::AccountId, other: &T::AccountId) -> bool {
+		CommunityMembership::<T>::iter_prefix(who)
+			.filter(|(_, role)| role.is_member())
+			.any(|(community_id, _)| {
+				CommunityMembership::<T>::get(other, community_id)
+					.map_or(false, |role| role.is_member())
+			})
 	}
 }
@@ -0,0 +1,184 @@
+use super::*;
+use frame_support::{storage_alias, traits::OnRuntimeUpgrade, weights::Weight};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+/// Migrate `CharTraits` and `Communities` from a single `BoundedVec` value
+/// into maps keyed by id
+pub mod v1 {
+	use super::*;
+
+	#[storage_alias]
+	pub(super) type CharTraits<T: Config> = StorageValue<
+		Pallet<T>,
+		BoundedVec<CharTraitOf<T>, <T as Config>::MaxCharTrait>,
+		ValueQuery,
+	>;
+
+	#[storage_alias]
+	pub(super) type Communities<T: Config> = StorageValue<
+		Pallet<T>,
+		BoundedVec<CommunityOf<T>, <T as Config>::MaxCommunities>,
+		ValueQuery,
+	>;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			let char_traits = CharTraits::<T>::get().len() as u32;
+			let communities = Communities::<T>::get().len() as u32;
+
+			Ok((char_traits, communities).encode())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			// Old values share the prefix with the new maps, so they should be
+			// taken before any new item is inserted
+			let char_traits = CharTraits::<T>::take();
+			let communities = Communities::<T>::take();
+			let items = (char_traits.len() + communities.len()) as u64;
+
+			char_traits.into_iter().for_each(|char_trait| {
+				crate::CharTraits::<T>::insert(char_trait.id, char_trait);
+			});
+			communities.into_iter().for_each(|community| {
+				crate::Communities::<T>::insert(community.id, community);
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			// Each insert also updates the counter of the map
+			T::DbWeight::get().reads_writes(3, 2 * items + 3)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let (char_traits, communities): (u32, u32) = Decode::decode(&mut &state[..])
+				.map_err(|_| TryRuntimeError::Other("Failed to decode pre upgrade state"))?;
+
+			ensure!(
+				crate::CharTraits::<T>::count() == char_traits,
+				"Number of char traits changed during migration"
+			);
+			ensure!(
+				crate::Communities::<T>::count() == communities,
+				"Number of communities changed during migration"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "Storage version wasn't updated");
+
+			Ok(())
+		}
+	}
+}
+
+/// Remove `Referral` flags, referral is now determined by the pallet itself
+pub mod v2 {
+	use super::*;
+	use frame_support::{storage::migration::clear_storage_prefix, traits::PalletInfoAccess};
+
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let result =
+				clear_storage_prefix(Pallet::<T>::name().as_bytes(), b"Referral", b"", None, None);
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(1, result.unique as u64 + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 2, "Storage version wasn't updated");
+
+			Ok(())
+		}
+	}
+}
+
+/// Set `DecayStartEra` to the current era, so trait scores earned before decay was
+/// enabled start decaying from the upgrade instead of genesis
+pub mod v3 {
+	use super::*;
+
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version >= 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			crate::DecayStartEra::<T>::put(Pallet::<T>::current_score_era().unwrap_or_default());
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(1, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 3, "Storage version wasn't updated");
+
+			Ok(())
+		}
+	}
+}
+
+/// Fill `CommunityAccounts` index with accounts which are members of the community or
+/// have a ban, invite or join request in it
+pub mod v4 {
+	use super::*;
+
+	pub struct MigrateToV4<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version >= 4 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut items = 0;
+			crate::CommunityMembership::<T>::iter_keys()
+				.chain(crate::BannedMembers::<T>::iter_keys())
+				.chain(crate::PendingInvites::<T>::iter_keys())
+				.chain(crate::JoinRequests::<T>::iter_keys())
+				.for_each(|(account_id, community_id)| {
+					crate::CommunityAccounts::<T>::insert(community_id, account_id, ());
+					items += 1;
+				});
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(items + 1, items + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				crate::CommunityMembership::<T>::iter_keys().all(|(account_id, community_id)| {
+					crate::CommunityAccounts::<T>::contains_key(community_id, account_id)
+				}),
+				"Community member is missing in community accounts"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 4, "Storage version wasn't updated");
+
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,41 @@
+use sp_common::types::Score;
+
+/// Information about appreciation used to weigh payee's trait score increment
+pub struct AppreciationContext<'a, Balance> {
+	/// Amount of tokens sent with appreciation
+	pub amount: Balance,
+	/// Payee appreciated payer within `ReciprocityWindow`
+	pub reciprocal: bool,
+	payer_score: &'a dyn Fn() -> Score,
+}
+
+impl<'a, Balance> AppreciationContext<'a, Balance> {
+	pub fn new(amount: Balance, reciprocal: bool, payer_score: &'a dyn Fn() -> Score) -> Self {
+		Self { amount, reciprocal, payer_score }
+	}
+
+	/// Karma score of the payer before the appreciation. It reads all trait scores
+	/// of the payer, so it is calculated on demand and should be paid with
+	/// `ScoreWeigher::weight`
+	pub fn payer_score(&self) -> Score {
+		(self.payer_score)()
+	}
+}
+
+/// Calculate how much payee's trait score is increased by appreciation
+pub trait ScoreWeigher<Balance> {
+	/// Returns trait score increment, zero means that score is not increased
+	fn weigh(context: &AppreciationContext<Balance>) -> Score;
+
+	/// Extra `ref_time` weight of `weigh` charged for each appreciation
+	fn weight() -> u64 {
+		0
+	}
+}
+
+/// Each appreciation increases trait score by one
+impl<Balance> ScoreWeigher<Balance> for () {
+	fn weigh(_context: &AppreciationContext<Balance>) -> Score {
+		1
+	}
+}
@@ -1,12 +1,15 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{traits::Get, BoundedVec, CloneNoBound, RuntimeDebugNoBound};
+use frame_support::{
+	traits::Get, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebug,
+	RuntimeDebugNoBound,
+};
 use scale_info::{prelude::string::String, TypeInfo};
 use serde::{Deserialize, Serialize};
 use sp_common::{
-	types::{CharTraitId, CommunityId},
+	types::{CharTraitId, CommunityId, Score},
 	BoundedString,
 };
-use sp_std::vec::Vec;
+use sp_std::{fmt::Debug, vec::Vec};
 
 pub type GenesisCommunity = (
 	CommunityId,
@@ -34,6 +37,9 @@ pub struct CharTrait<CharNameLimit: Get<u32>, EmojiLimit: Get<u32>> {
 
 #[derive(
 	Clone,
+	Copy,
+	PartialEq,
+	Eq,
 	Encode,
 	Decode,
 	MaxEncodedLen,
@@ -44,15 +50,60 @@ pub struct CharTrait<CharNameLimit: Get<u32>, EmojiLimit: Get<u32>> {
 	Deserialize,
 )]
 pub enum CommunityRole {
+	#[codec(index = 0)]
 	Admin,
+	#[codec(index = 1)]
 	Member,
 	#[default]
+	#[codec(index = 2)]
 	None,
+	/// Community owner. There is only one owner per community
+	#[codec(index = 3)]
+	Owner,
+	/// Member who can invite new members, but can't manage the community
+	#[codec(index = 4)]
+	Moderator,
 }
 
 impl CommunityRole {
+	/// Owner and admins are the community admins
 	pub fn is_admin(&self) -> bool {
-		matches!(self, Self::Admin)
+		matches!(self, Self::Owner | Self::Admin)
+	}
+
+	pub fn is_member(&self) -> bool {
+		!matches!(self, Self::None)
+	}
+
+	/// Position of the role in the community hierarchy
+	pub fn rank(&self) -> u8 {
+		match self {
+			Self::None => 0,
+			Self::Member => 1,
+			Self::Moderator => 2,
+			Self::Admin => 3,
+			Self::Owner => 4,
+		}
+	}
+
+	/// Can invite new members and approve join requests
+	pub fn can_invite(&self) -> bool {
+		self.rank() >= Self::Moderator.rank()
+	}
+
+	/// Can bring new members into closed community by appreciation
+	pub fn can_appreciate_into_closed(&self) -> bool {
+		self.rank() >= Self::Moderator.rank()
+	}
+
+	/// Can change community metadata
+	pub fn can_edit_metadata(&self) -> bool {
+		self.is_admin()
+	}
+
+	/// Can remove, ban, demote or promote account with `other` role
+	pub fn can_manage(&self, other: &Self) -> bool {
+		self.is_admin() && self.rank() > other.rank()
 	}
 }
 
@@ -81,3 +132,59 @@ pub struct Community<
 	/// and only members can appreciate each other in the community
 	pub closed: bool,
 }
+
+/// Appreciation which can be undone by the payer during `AppreciationUndoPeriod`
+#[derive(Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct RevocableAppreciation<Balance, BlockNumber> {
+	pub amount: Balance,
+	pub community_id: CommunityId,
+	pub char_trait_id: CharTraitId,
+	/// Payee's trait score increment
+	pub payee_score: Score,
+	/// Payer got ambassador score for the referral
+	pub referral: bool,
+	/// Payee joined the community with this appreciation, so payer got ambassador score
+	pub new_member: bool,
+	/// Community bonus paid to the payee with the appreciation
+	pub bonus: Balance,
+	/// Payee's free balance right after the appreciation
+	pub payee_balance: Balance,
+	pub appreciated_at: BlockNumber,
+}
+
+/// Appreciation to unregistered phone number, funds of which are kept
+/// in the escrow account until the user signs up
+#[derive(
+	CloneNoBound,
+	PartialEqNoBound,
+	EqNoBound,
+	Encode,
+	Decode,
+	MaxEncodedLen,
+	TypeInfo,
+	RuntimeDebugNoBound,
+)]
+#[codec(mel_bound(Balance: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
+#[scale_info(skip_type_params(MemoLimit))]
+pub struct EscrowedAppreciation<
+	Balance: Clone + PartialEq + Eq + Debug,
+	BlockNumber: Clone + PartialEq + Eq + Debug,
+	MemoLimit: Get<u32>,
+> {
+	pub amount: Balance,
+	pub community_id: CommunityId,
+	pub char_trait_id: CharTraitId,
+	/// Memo of the appreciation, which is emitted when appreciation is released
+	pub memo: Option<BoundedString<MemoLimit>>,
+	/// After this block payer can take the funds back
+	pub expires_at: BlockNumber,
+	/// Order in which appreciations were escrowed, the earliest one counts as a referral
+	pub index: u64,
+}
+
+/// Progress of the community removal, which is done by several `remove_community` calls
+#[derive(Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug, Default)]
+pub struct CommunityRemoval {
+	/// Number of members whose membership was removed so far
+	pub members: u32,
+}
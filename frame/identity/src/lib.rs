@@ -1,31 +1,65 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod traits;
 pub mod types;
 
-use crate::types::{IdentityStore, VerificationResult};
+use crate::{
+	traits::UsernameValidator,
+	types::{
+		ActiveRecovery, EvidenceVersion, IdentityStore, RecoveryConfig, VerificationResult,
+		VerifierInfo,
+	},
+};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	pallet_prelude::DispatchResult,
+	ensure,
+	pallet_prelude::{DispatchError, DispatchResult},
 	traits::{Currency, ExistenceRequirement, Get},
 	BoundedVec, PalletId,
 };
+use frame_system::pallet_prelude::BlockNumberFor;
 pub use pallet::*;
+use scale_info::prelude::string::String;
 use sp_common::{
 	hooks::Hooks,
 	identity::{AccountIdentity, IdentityInfo},
-	traits::{IdentityProvider, MaybeNormalized},
+	traits::{CommunityMembershipProvider, IdentityProvider, MaybeNormalized},
 	BoundedString,
 };
-use sp_rpc::VerificationEvidence;
-use sp_runtime::traits::{AccountIdConversion, IdentifyAccount, Verify};
+use sp_rpc::{VerificationEvidence, VerificationEvidenceV2, VersionedVerificationEvidence};
+use sp_runtime::traits::{AccountIdConversion, IdentifyAccount, Saturating, Verify, Zero};
 use sp_std::{prelude::*, vec};
 
+pub type RecoveryConfigOf<T> =
+	RecoveryConfig<<T as frame_system::Config>::AccountId, <T as Config>::MaxGuardians>;
+
+pub type ActiveRecoveryOf<T> = ActiveRecovery<
+	<T as frame_system::Config>::AccountId,
+	BlockNumberFor<T>,
+	<T as Config>::MaxGuardians,
+>;
+
+pub type VerifierInfoOf<T> =
+	VerifierInfo<<T as Config>::VerifierNameLimit, <T as Config>::VerifierUrlLimit>;
+
+/// Latest supported version of `VerificationEvidence`
+pub const LATEST_EVIDENCE_VERSION: u8 = 2;
+
+/// Phone verifiers public keys with signatures of the same verification evidence
+pub type Attestations<T> = BoundedVec<
+	(<T as Config>::PublicKey, <T as Config>::Signature),
+	<T as Config>::MaxPhoneVerifiers,
+>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::{pallet_prelude::*, traits::Currency};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, Hooks},
+	};
 	use frame_system::pallet_prelude::*;
-	use sp_common::hooks::Hooks;
+	use sp_common::hooks::Hooks as KarmaHooks;
 	use sp_std::fmt::Debug;
 
 	#[pallet::config]
@@ -52,7 +86,12 @@ pub mod pallet {
 		/// Max number of phone verifiers allowed
 		type MaxPhoneVerifiers: Get<u32>;
 		/// Handler for when a new user has just been registered
-		type Hooks: Hooks<Self::AccountId, Self::Balance, Self::Username, Self::PhoneNumberHash>;
+		type Hooks: KarmaHooks<
+			Self::AccountId,
+			Self::Balance,
+			Self::Username,
+			Self::PhoneNumberHash,
+		>;
 		/// The currency mechanism.
 		type Currency: Currency<Self::AccountId, Balance = Self::Balance>;
 
@@ -76,6 +115,32 @@ pub mod pallet {
 		/// The maximum length of metadata per account
 		#[pallet::constant]
 		type MaxMetadataLength: Get<u32>;
+
+		/// Number of blocks during which released username is reserved for the previous owner
+		#[pallet::constant]
+		type UsernameReservationPeriod: Get<BlockNumberFor<Self>>;
+
+		/// Rules for usernames taken via `new_user`, `update_user` and genesis
+		type UsernameValidator: UsernameValidator<Self::Username>;
+
+		/// Max length of phone verifier name and region
+		#[pallet::constant]
+		type VerifierNameLimit: Get<u32>;
+
+		/// Max length of phone verifier contact URL
+		#[pallet::constant]
+		type VerifierUrlLimit: Get<u32>;
+
+		/// Max number of guardians who can recover the account
+		#[pallet::constant]
+		type MaxGuardians: Get<u32>;
+
+		/// Number of blocks after which not completed recovery expires and can be replaced
+		#[pallet::constant]
+		type RecoveryExpiration: Get<BlockNumberFor<Self>>;
+
+		/// Provides community membership to check that guardians share community with the user
+		type Communities: CommunityMembershipProvider<Self::AccountId>;
 	}
 
 	#[pallet::pallet]
@@ -85,11 +150,25 @@ pub mod pallet {
 	pub struct GenesisConfig<T: Config> {
 		pub phone_verifiers: sp_std::vec::Vec<T::AccountId>,
 		pub identities: sp_std::vec::Vec<(T::AccountId, T::Username, T::PhoneNumberHash)>,
+		pub reserved_usernames: sp_std::vec::Vec<T::Username>,
+		pub verification_threshold: u32,
+		/// Name, contact URL and region of phone verifiers
+		pub phone_verifiers_info: sp_std::vec::Vec<(T::AccountId, String, String, String)>,
+		/// Oldest accepted version of `VerificationEvidence`. `V1` evidence has no replay
+		/// protection, so new networks should start with `LATEST_EVIDENCE_VERSION`
+		pub minimum_evidence_version: u8,
 	}
 
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			Self { phone_verifiers: vec![], identities: vec![] }
+			Self {
+				phone_verifiers: vec![],
+				identities: vec![],
+				reserved_usernames: vec![],
+				verification_threshold: 1,
+				phone_verifiers_info: vec![],
+				minimum_evidence_version: 1,
+			}
 		}
 	}
 
@@ -101,10 +180,46 @@ pub mod pallet {
 					"Initial number of phone_verifiers should be lower than T::MaxPhoneVerifiers",
 				);
 			PhoneVerifiers::<T>::put(bounded_phone_verifiers);
+			assert!(
+				self.verification_threshold as usize <= self.phone_verifiers.len().max(1),
+				"Verification threshold should not exceed number of phone_verifiers"
+			);
+			VerificationThreshold::<T>::put(self.verification_threshold);
+			assert!(
+				self.minimum_evidence_version <= LATEST_EVIDENCE_VERSION,
+				"Minimum evidence version should not exceed LATEST_EVIDENCE_VERSION"
+			);
+			MinimumEvidenceVersion::<T>::put(self.minimum_evidence_version);
+
+			for (account_id, name, url, region) in &self.phone_verifiers_info {
+				assert!(
+					self.phone_verifiers.contains(account_id),
+					"Phone verifier info should be provided only for phone_verifiers"
+				);
+				PhoneVerifierInfo::<T>::insert(
+					account_id,
+					VerifierInfo {
+						name: name.clone().try_into().expect(
+							"Max length of phone verifier name should be lower than T::VerifierNameLimit",
+						),
+						url: url.clone().try_into().expect(
+							"Max length of phone verifier url should be lower than T::VerifierUrlLimit",
+						),
+						region: region.clone().try_into().expect(
+							"Max length of phone verifier region should be lower than T::VerifierNameLimit",
+						),
+						suspended: false,
+					},
+				);
+			}
 
 			for (account_id, username, phone_number_hash) in &self.identities {
 				// Cast username to lowercase
 				let username = username.clone().normalize();
+				assert!(
+					T::UsernameValidator::is_valid(&username),
+					"Genesis username should be accepted by T::UsernameValidator"
+				);
 
 				IdentityOf::<T>::insert(
 					&account_id,
@@ -117,6 +232,10 @@ pub mod pallet {
 				UsernameFor::<T>::insert(&username, &account_id);
 				PhoneNumberFor::<T>::insert(&phone_number_hash, &account_id);
 			}
+
+			for username in &self.reserved_usernames {
+				ReservedUsernames::<T>::insert(username.clone().normalize(), ());
+			}
 		}
 	}
 
@@ -140,6 +259,67 @@ pub mod pallet {
 	pub type PhoneVerifiers<T: Config> =
 		StorageValue<_, BoundedVec<T::AccountId, T::MaxPhoneVerifiers>, ValueQuery>;
 
+	/// Metadata of phone verifiers
+	#[pallet::storage]
+	pub type PhoneVerifierInfo<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, VerifierInfoOf<T>>;
+
+	/// Number of distinct phone verifiers which should attest phone number
+	/// in `new_user` and `update_user` transactions
+	#[pallet::storage]
+	pub type VerificationThreshold<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Oldest version of `VerificationEvidence` accepted from phone verifiers
+	#[pallet::storage]
+	pub type MinimumEvidenceVersion<T: Config> = StorageValue<_, u8, ValueQuery>;
+
+	/// Nonces of `VerificationEvidence` already used for the phone number
+	#[pallet::storage]
+	pub type UsedEvidenceNonces<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PhoneNumberHash, Twox64Concat, u64, ()>;
+
+	/// Phone verifier which verified the current phone number of the `AccountId`
+	#[pallet::storage]
+	pub type VerifiedBy<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
+
+	/// Number of `new_user` registrations and migrations verified by the phone verifier
+	#[pallet::storage]
+	pub type VerifierRegistrations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+	/// Usernames which can't be taken by users
+	#[pallet::storage]
+	pub type ReservedUsernames<T: Config> = StorageMap<_, Blake2_128Concat, T::Username, ()>;
+
+	/// Released usernames with the previous owner and the block number
+	/// until which the username is reserved for this owner
+	#[pallet::storage]
+	pub type ReleasedUsernames<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Username, (T::AccountId, BlockNumberFor<T>)>;
+
+	/// Released usernames by the block number when their reservation expires.
+	/// Used to prune expired `ReleasedUsernames`
+	#[pallet::storage]
+	pub type ReleasedUsernameExpirations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		Blake2_128Concat,
+		T::Username,
+		(),
+		OptionQuery,
+	>;
+
+	/// Guardians who can recover the account and number of required approvals
+	#[pallet::storage]
+	pub type RecoveryConfigs<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, RecoveryConfigOf<T>>;
+
+	/// Recoveries of lost accounts waiting for guardians approvals
+	#[pallet::storage]
+	pub type ActiveRecoveries<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ActiveRecoveryOf<T>>;
+
 	/// Store metadata per `AccountId`
 	#[pallet::storage]
 	#[pallet::getter(fn metadata)]
@@ -165,6 +345,50 @@ pub mod pallet {
 		/// Provided `Username` or `PhoneNumber` to update user data must be different from
 		/// existed one. Or missed parameters for update
 		InvalidArguments,
+		/// Username is reserved or recently released by another user
+		UsernameReserved,
+		/// Username is rejected by `UsernameValidator`
+		InvalidUsername,
+		/// Phone number is attested by less phone verifiers than `VerificationThreshold`
+		NotEnoughAttestations,
+		/// Phone number is attested by the same phone verifier more than once
+		DuplicateAttestation,
+		/// Verification threshold is zero or exceeds number of not suspended phone verifiers
+		InvalidVerificationThreshold,
+		/// `VerificationEvidence` version is lower than `MinimumEvidenceVersion`
+		EvidenceVersionTooOld,
+		/// Unknown `VerificationEvidence` version
+		InvalidEvidenceVersion,
+		/// `VerificationEvidence` is expired
+		EvidenceExpired,
+		/// `VerificationEvidence` nonce is already used for this phone number
+		EvidenceAlreadyUsed,
+		/// Phone verifier with this `AccountId` already exists
+		VerifierAlreadyExists,
+		/// Number of phone verifiers reached `MaxPhoneVerifiers`
+		TooManyVerifiers,
+		/// Phone verifier is suspended and can't attest phone numbers
+		VerifierSuspended,
+		/// Guardian is the user itself, duplicated or not registered
+		InvalidGuardian,
+		/// Guardian isn't a member of any community of the user
+		GuardianNotInCommunity,
+		/// Recovery threshold is zero or exceeds number of guardians
+		InvalidRecoveryThreshold,
+		/// Account has no guardians
+		RecoveryNotConfigured,
+		/// Recovery of the account is already initiated
+		RecoveryAlreadyActive,
+		/// Recovery of the account isn't initiated
+		RecoveryNotActive,
+		/// Caller isn't a guardian of the account
+		NotGuardian,
+		/// Guardian already approved the recovery
+		AlreadyApproved,
+		/// Recovery wasn't completed during `RecoveryExpiration`
+		RecoveryExpired,
+		/// Active recovery moves the account to another new account
+		RecoveryAccountMismatch,
 	}
 
 	#[pallet::event]
@@ -197,10 +421,58 @@ pub mod pallet {
 			username: T::Username,
 			phone_number_hash: T::PhoneNumberHash,
 		},
+		/// Happens when `add_reserved_username` tx happen
+		ReservedUsernameAdded { username: T::Username },
+		/// Happens when `remove_reserved_username` tx happen
+		ReservedUsernameRemoved { username: T::Username },
+		/// Happens when `set_verification_threshold` tx happen
+		VerificationThresholdSet { threshold: u32 },
+		/// Happens when `set_minimum_evidence_version` tx happen
+		MinimumEvidenceVersionSet { version: u8 },
+		/// Happens when `add_phone_verifier` tx happen
+		PhoneVerifierAdded { account_id: T::AccountId },
+		/// Happens when `set_phone_verifier_info` tx happen
+		PhoneVerifierInfoUpdated { account_id: T::AccountId },
+		/// Happens when `rotate_phone_verifier_key` tx happen
+		PhoneVerifierKeyRotated { old_account_id: T::AccountId, new_account_id: T::AccountId },
+		/// Happens when `suspend_phone_verifier` tx happen
+		PhoneVerifierSuspended { account_id: T::AccountId },
+		/// Happens when `resume_phone_verifier` tx happen
+		PhoneVerifierResumed { account_id: T::AccountId },
+		/// Happens when `remove_phone_verifier` tx happen
+		PhoneVerifierRemoved { account_id: T::AccountId },
+		/// Happens when `set_guardians` tx happen
+		GuardiansSet { account_id: T::AccountId, threshold: u32 },
+		/// Happens when `remove_guardians` tx happen
+		GuardiansRemoved { account_id: T::AccountId },
+		/// Happens when `initiate_recovery` tx happen
+		RecoveryInitiated { lost_account_id: T::AccountId, new_account_id: T::AccountId },
+		/// Happens when `approve_recovery` tx happen
+		RecoveryApproved { lost_account_id: T::AccountId, guardian: T::AccountId },
+		/// Happens when `cancel_recovery` tx happen
+		RecoveryCancelled { lost_account_id: T::AccountId },
+		/// User identity moved to new `AccountId` by guardians
+		AccountRecovered { old_account_id: T::AccountId, new_account_id: T::AccountId },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			// Usernames expiring at the same block were released by extrinsics of one block,
+			// so their number is bounded by the block weight
+			let pruned = Self::prune_released_usernames(now);
+
+			T::DbWeight::get().reads_writes(1 + pruned, 2 * pruned)
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Create new user with `V1` evidence signed by the phone verifier.
+		///
+		/// `V1` evidence has no nonce or expiration, so it can be replayed, e.g. to
+		/// register the phone number again after `delete_user`. It is rejected when
+		/// `MinimumEvidenceVersion` is above 1, use `new_user_attested` with `V2` evidence
 		#[pallet::call_index(0)]
 		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
 		pub fn new_user(
@@ -212,47 +484,28 @@ pub mod pallet {
 			phone_number_hash: T::PhoneNumberHash,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(who == account_id, Error::<T>::AccountIdMismatch);
-
-			// Cast username to lowercase
-			let username = username.normalize();
 
-			let verifier_account_id = verifier_public_key.clone().into();
-			// Check verification
-			ensure!(
-				PhoneVerifiers::<T>::get().contains(&verifier_account_id),
-				Error::<T>::NotVerifier
-			);
-			ensure!(
-				Self::verify_signature(
-					verifier_public_key,
-					verifier_signature,
-					account_id.clone(),
-					username.clone(),
-					phone_number_hash.clone()
-				),
-				Error::<T>::InvalidSignature
-			);
-
-			let verifier_account_id =
-				PhoneVerifiers::<T>::get().pop().ok_or(Error::<T>::NotVerifier)?;
-
-			match Self::verify(&account_id, &username, &phone_number_hash) {
-				VerificationResult::Valid => Self::register_user(
-					verifier_account_id,
-					account_id,
-					username,
-					phone_number_hash,
-				),
-				VerificationResult::Migration =>
-					Self::migrate_user(verifier_account_id, account_id, phone_number_hash),
-				VerificationResult::AccountIdExists => Err(Error::<T>::AlreadyRegistered.into()),
-				VerificationResult::UsernameExists => Err(Error::<T>::UserNameTaken.into()),
-			}
+			Self::do_new_user(
+				who,
+				&[(verifier_public_key, verifier_signature)],
+				EvidenceVersion::V1,
+				account_id,
+				username,
+				phone_number_hash,
+			)
 		}
 
+		/// Update username or phone number of the user. Phone number change should be
+		/// signed by the phone verifier with `V1` evidence.
+		///
+		/// `V1` evidence has no replay protection, so phone number change is rejected when
+		/// `MinimumEvidenceVersion` is above 1, use `update_user_attested` with `V2` evidence
 		#[pallet::call_index(1)]
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		#[pallet::weight(
+			10_000 +
+				T::DbWeight::get().reads_writes(3, 1).ref_time() +
+				T::Hooks::on_update_user_weight().ref_time()
+		)]
 		pub fn update_user(
 			origin: OriginFor<T>,
 			verifier_public_key: Option<T::PublicKey>,
@@ -261,80 +514,16 @@ pub mod pallet {
 			phone_number_hash: Option<T::PhoneNumberHash>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(
-				username.is_some() || phone_number_hash.is_some(),
-				Error::<T>::InvalidArguments
-			);
-			ensure!(IdentityOf::<T>::contains_key(&who), Error::<T>::NotFound);
-			// Safety: because of check above unwrap do not panics
-			let mut identity = IdentityOf::<T>::get(&who).unwrap();
-
-			if let Some(username) = username.clone() {
-				// Cast username to lowercase
-				let username = username.normalize();
-
-				ensure!(identity.username != username, Error::<T>::InvalidArguments);
-				// Check username for uniqueness
-				ensure!(!UsernameFor::<T>::contains_key(&username), Error::<T>::UserNameTaken);
-				// Remove old `Username` <-> `AccountId` relation
-				UsernameFor::<T>::remove(identity.username);
-				UsernameFor::<T>::insert(&username, &who);
-				// Set new username
-				identity.username = username;
-			}
-
-			if let Some(phone_number_hash) = phone_number_hash.clone() {
-				let verifier_public_key =
-					verifier_public_key.ok_or(Error::<T>::InvalidArguments)?;
-				let verifier_signature = verifier_signature.ok_or(Error::<T>::InvalidArguments)?;
-
-				let verifier_account_id = verifier_public_key.clone().into();
-				// Check verification
-				ensure!(
-					PhoneVerifiers::<T>::get().contains(&verifier_account_id),
-					Error::<T>::NotVerifier
-				);
-				ensure!(
-					Self::verify_signature(
-						verifier_public_key,
-						verifier_signature,
-						who.clone(),
-						identity.username.clone(),
-						phone_number_hash.clone()
-					),
-					Error::<T>::InvalidSignature
-				);
-
-				ensure!(
-					identity.phone_number_hash != phone_number_hash,
-					Error::<T>::InvalidArguments
-				);
-				// Check phone number for uniqueness
-				ensure!(
-					!PhoneNumberFor::<T>::contains_key(&phone_number_hash),
-					Error::<T>::PhoneNumberTaken
-				);
-				// Remove old `PhoneNumber` <-> `AccountId` relation
-				PhoneNumberFor::<T>::remove(identity.phone_number_hash);
-				PhoneNumberFor::<T>::insert(&phone_number_hash, &who);
-				// Set new phone number
-				identity.phone_number_hash = phone_number_hash;
-			}
-
-			// Save identity changes
-			IdentityOf::<T>::insert(&who, &identity);
-
-			// TODO: call hook
-
-			Self::deposit_event(Event::<T>::AccountUpdated {
-				account_id: who,
-				username: identity.username,
-				new_username: username,
-				phone_number_hash: identity.phone_number_hash,
-				new_phone_number_hash: phone_number_hash,
-			});
-
-			Ok(())
+			let attestations: Vec<_> =
+				verifier_public_key.zip(verifier_signature).into_iter().collect();
+
+			Self::do_update_user(
+				who,
+				&attestations,
+				EvidenceVersion::V1,
+				username,
+				phone_number_hash,
+			)
 		}
 
 		#[pallet::call_index(2)]
@@ -344,7 +533,11 @@ pub mod pallet {
 
 			let identity_info = IdentityOf::<T>::take(&who).ok_or(Error::<T>::NotFound)?;
 			UsernameFor::<T>::remove(&identity_info.username);
+			Self::release_username(identity_info.username.clone(), &who);
 			PhoneNumberFor::<T>::remove(&identity_info.phone_number_hash);
+			VerifiedBy::<T>::remove(&who);
+			RecoveryConfigs::<T>::remove(&who);
+			ActiveRecoveries::<T>::remove(&who);
 
 			let balance = T::Currency::free_balance(&who);
 			T::Currency::transfer(
@@ -393,6 +586,465 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Reserve `username`, so users can't take it. Doesn't affect user who already
+		/// has this username
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn add_reserved_username(
+			origin: OriginFor<T>,
+			username: T::Username,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			let username = username.normalize();
+			ReservedUsernames::<T>::insert(&username, ());
+
+			Self::deposit_event(Event::<T>::ReservedUsernameAdded { username });
+
+			Ok(())
+		}
+
+		/// Remove `username` from reserved usernames
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(6)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn remove_reserved_username(
+			origin: OriginFor<T>,
+			username: T::Username,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			let username = username.normalize();
+			ensure!(ReservedUsernames::<T>::contains_key(&username), Error::<T>::NotFound);
+			ReservedUsernames::<T>::remove(&username);
+
+			Self::deposit_event(Event::<T>::ReservedUsernameRemoved { username });
+
+			Ok(())
+		}
+
+		/// Set number of distinct phone verifiers which should attest phone number.
+		/// Threshold can't exceed number of not suspended phone verifiers
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(7)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1 + T::MaxPhoneVerifiers::get() as u64, 1).ref_time())]
+		pub fn set_verification_threshold(origin: OriginFor<T>, threshold: u32) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(
+				threshold > 0 && threshold <= Self::active_verifiers_count(),
+				Error::<T>::InvalidVerificationThreshold
+			);
+			VerificationThreshold::<T>::put(threshold);
+
+			Self::deposit_event(Event::<T>::VerificationThresholdSet { threshold });
+
+			Ok(())
+		}
+
+		/// Same as `new_user`, but phone number is attested by several phone verifiers
+		/// with `evidence_version` of signed `VerificationEvidence`
+		#[pallet::call_index(8)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 2).ref_time())]
+		pub fn new_user_attested(
+			origin: OriginFor<T>,
+			attestations: Attestations<T>,
+			evidence_version: EvidenceVersion<BlockNumberFor<T>>,
+			account_id: T::AccountId,
+			username: T::Username,
+			phone_number_hash: T::PhoneNumberHash,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::do_new_user(
+				who,
+				&attestations,
+				evidence_version,
+				account_id,
+				username,
+				phone_number_hash,
+			)
+		}
+
+		/// Same as `update_user`, but phone number is attested by several phone verifiers
+		/// with `evidence_version` of signed `VerificationEvidence`
+		#[pallet::call_index(9)]
+		#[pallet::weight(
+			10_000 +
+				T::DbWeight::get().reads_writes(4, 2).ref_time() +
+				T::Hooks::on_update_user_weight().ref_time()
+		)]
+		pub fn update_user_attested(
+			origin: OriginFor<T>,
+			attestations: Attestations<T>,
+			evidence_version: EvidenceVersion<BlockNumberFor<T>>,
+			username: Option<T::Username>,
+			phone_number_hash: Option<T::PhoneNumberHash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::do_update_user(who, &attestations, evidence_version, username, phone_number_hash)
+		}
+
+		/// Add new phone verifier with its name, contact URL and region
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(10)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 2).ref_time())]
+		pub fn add_phone_verifier(
+			origin: OriginFor<T>,
+			account_id: T::AccountId,
+			name: BoundedString<T::VerifierNameLimit>,
+			url: BoundedString<T::VerifierUrlLimit>,
+			region: BoundedString<T::VerifierNameLimit>,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			PhoneVerifiers::<T>::try_mutate(|verifiers| -> DispatchResult {
+				ensure!(!verifiers.contains(&account_id), Error::<T>::VerifierAlreadyExists);
+				verifiers
+					.try_push(account_id.clone())
+					.map_err(|_| Error::<T>::TooManyVerifiers)?;
+
+				Ok(())
+			})?;
+			PhoneVerifierInfo::<T>::insert(
+				&account_id,
+				VerifierInfo { name, url, region, suspended: false },
+			);
+
+			Self::deposit_event(Event::<T>::PhoneVerifierAdded { account_id });
+
+			Ok(())
+		}
+
+		/// Set name, contact URL and region of the phone verifier
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(11)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn set_phone_verifier_info(
+			origin: OriginFor<T>,
+			account_id: T::AccountId,
+			name: BoundedString<T::VerifierNameLimit>,
+			url: BoundedString<T::VerifierUrlLimit>,
+			region: BoundedString<T::VerifierNameLimit>,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(PhoneVerifiers::<T>::get().contains(&account_id), Error::<T>::NotVerifier);
+			let suspended = Self::is_suspended(&account_id);
+			PhoneVerifierInfo::<T>::insert(
+				&account_id,
+				VerifierInfo { name, url, region, suspended },
+			);
+
+			Self::deposit_event(Event::<T>::PhoneVerifierInfoUpdated { account_id });
+
+			Ok(())
+		}
+
+		/// Replace key of the phone verifier keeping its info and registrations count
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(12)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 5).ref_time())]
+		pub fn rotate_phone_verifier_key(
+			origin: OriginFor<T>,
+			old_account_id: T::AccountId,
+			new_account_id: T::AccountId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			PhoneVerifiers::<T>::try_mutate(|verifiers| -> DispatchResult {
+				ensure!(!verifiers.contains(&new_account_id), Error::<T>::VerifierAlreadyExists);
+				let verifier = verifiers
+					.iter_mut()
+					.find(|verifier| **verifier == old_account_id)
+					.ok_or(Error::<T>::NotVerifier)?;
+				*verifier = new_account_id.clone();
+				Ok(())
+			})?;
+			if let Some(info) = PhoneVerifierInfo::<T>::take(&old_account_id) {
+				PhoneVerifierInfo::<T>::insert(&new_account_id, info);
+			}
+			let registrations = VerifierRegistrations::<T>::take(&old_account_id);
+			VerifierRegistrations::<T>::insert(&new_account_id, registrations);
+
+			Self::deposit_event(Event::<T>::PhoneVerifierKeyRotated {
+				old_account_id,
+				new_account_id,
+			});
+
+			Ok(())
+		}
+
+		/// Suspend phone verifier, so it can't attest phone numbers. Number of not suspended
+		/// phone verifiers can't become lower than `VerificationThreshold`
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(13)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3 + T::MaxPhoneVerifiers::get() as u64, 1).ref_time())]
+		pub fn suspend_phone_verifier(
+			origin: OriginFor<T>,
+			account_id: T::AccountId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			Self::set_suspended(&account_id, true)?;
+			ensure!(
+				Self::active_verifiers_count() >= VerificationThreshold::<T>::get(),
+				Error::<T>::InvalidVerificationThreshold
+			);
+
+			Self::deposit_event(Event::<T>::PhoneVerifierSuspended { account_id });
+
+			Ok(())
+		}
+
+		/// Resume suspended phone verifier
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(14)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn resume_phone_verifier(
+			origin: OriginFor<T>,
+			account_id: T::AccountId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			Self::set_suspended(&account_id, false)?;
+
+			Self::deposit_event(Event::<T>::PhoneVerifierResumed { account_id });
+
+			Ok(())
+		}
+
+		/// Remove phone verifier. Number of remaining not suspended phone verifiers
+		/// can't become lower than `VerificationThreshold`
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(15)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2 + T::MaxPhoneVerifiers::get() as u64, 2).ref_time())]
+		pub fn remove_phone_verifier(
+			origin: OriginFor<T>,
+			account_id: T::AccountId,
+		) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			PhoneVerifiers::<T>::try_mutate(|verifiers| -> DispatchResult {
+				ensure!(verifiers.contains(&account_id), Error::<T>::NotVerifier);
+				verifiers.retain(|verifier| verifier != &account_id);
+				let active_verifiers =
+					verifiers.iter().filter(|verifier| !Self::is_suspended(verifier)).count();
+				ensure!(
+					active_verifiers as u32 >= VerificationThreshold::<T>::get(),
+					Error::<T>::InvalidVerificationThreshold
+				);
+				Ok(())
+			})?;
+			PhoneVerifierInfo::<T>::remove(&account_id);
+
+			Self::deposit_event(Event::<T>::PhoneVerifierRemoved { account_id });
+
+			Ok(())
+		}
+
+		/// Set oldest version of `VerificationEvidence` accepted from phone verifiers.
+		/// `new_user` and `update_user` txs accept only `V1` evidence, which has no replay
+		/// protection, so they are disabled by any version above 1
+		///
+		/// Can only be called by Root origin (Sudo). Use Sudo::call to call this transaction
+		#[pallet::call_index(16)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_minimum_evidence_version(origin: OriginFor<T>, version: u8) -> DispatchResult {
+			// Only sudo can call
+			ensure_root(origin)?;
+
+			ensure!(
+				(1..=LATEST_EVIDENCE_VERSION).contains(&version),
+				Error::<T>::InvalidEvidenceVersion
+			);
+			MinimumEvidenceVersion::<T>::put(version);
+
+			Self::deposit_event(Event::<T>::MinimumEvidenceVersionSet { version });
+
+			Ok(())
+		}
+
+		/// Set guardians who can recover sender account and number of required approvals.
+		/// Guardians should be registered users sharing a community with the sender
+		#[pallet::call_index(17)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1 + 2 * T::MaxGuardians::get() as u64, 1).ref_time())]
+		pub fn set_guardians(
+			origin: OriginFor<T>,
+			guardians: BoundedVec<T::AccountId, T::MaxGuardians>,
+			threshold: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(IdentityOf::<T>::contains_key(&who), Error::<T>::NotFound);
+
+			for (index, guardian) in guardians.iter().enumerate() {
+				ensure!(
+					guardian != &who &&
+						!guardians[..index].contains(guardian) &&
+						IdentityOf::<T>::contains_key(guardian),
+					Error::<T>::InvalidGuardian
+				);
+				ensure!(
+					T::Communities::share_community(&who, guardian),
+					Error::<T>::GuardianNotInCommunity
+				);
+			}
+			ensure!(
+				threshold > 0 && threshold as usize <= guardians.len(),
+				Error::<T>::InvalidRecoveryThreshold
+			);
+
+			RecoveryConfigs::<T>::insert(&who, RecoveryConfig { guardians, threshold });
+
+			Self::deposit_event(Event::<T>::GuardiansSet { account_id: who, threshold });
+
+			Ok(())
+		}
+
+		/// Remove guardians of sender account and cancel active recovery
+		#[pallet::call_index(18)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn remove_guardians(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			RecoveryConfigs::<T>::take(&who).ok_or(Error::<T>::RecoveryNotConfigured)?;
+			ActiveRecoveries::<T>::remove(&who);
+
+			Self::deposit_event(Event::<T>::GuardiansRemoved { account_id: who });
+
+			Ok(())
+		}
+
+		/// Initiate recovery of `lost_account_id` to `new_account_id`, which shouldn't be
+		/// registered. Expired recovery is replaced.
+		///
+		/// Can only be called by guardian of the lost account
+		#[pallet::call_index(19)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 1).ref_time())]
+		pub fn initiate_recovery(
+			origin: OriginFor<T>,
+			lost_account_id: T::AccountId,
+			new_account_id: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let recovery_config = RecoveryConfigs::<T>::get(&lost_account_id)
+				.ok_or(Error::<T>::RecoveryNotConfigured)?;
+			ensure!(recovery_config.guardians.contains(&who), Error::<T>::NotGuardian);
+			ensure!(IdentityOf::<T>::contains_key(&who), Error::<T>::NotGuardian);
+			ensure!(!IdentityOf::<T>::contains_key(&new_account_id), Error::<T>::AlreadyRegistered);
+			ensure!(
+				ActiveRecoveries::<T>::get(&lost_account_id)
+					.map_or(true, |recovery| Self::is_recovery_expired(&recovery)),
+				Error::<T>::RecoveryAlreadyActive
+			);
+
+			ActiveRecoveries::<T>::insert(
+				&lost_account_id,
+				ActiveRecovery {
+					new_account_id: new_account_id.clone(),
+					approvals: Default::default(),
+					initiated_at: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+
+			Self::deposit_event(Event::<T>::RecoveryInitiated { lost_account_id, new_account_id });
+
+			Ok(())
+		}
+
+		/// Approve active recovery of `lost_account_id` to `new_account_id`. Identity, balance,
+		/// trait scores, etc are moved to the new account once number of approvals reach
+		/// the threshold. Fails if the active recovery moves the account elsewhere
+		#[pallet::call_index(20)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(8, 8).ref_time())]
+		pub fn approve_recovery(
+			origin: OriginFor<T>,
+			lost_account_id: T::AccountId,
+			new_account_id: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let recovery_config = RecoveryConfigs::<T>::get(&lost_account_id)
+				.ok_or(Error::<T>::RecoveryNotConfigured)?;
+			ensure!(recovery_config.guardians.contains(&who), Error::<T>::NotGuardian);
+			ensure!(IdentityOf::<T>::contains_key(&who), Error::<T>::NotGuardian);
+
+			let mut recovery = ActiveRecoveries::<T>::get(&lost_account_id)
+				.ok_or(Error::<T>::RecoveryNotActive)?;
+			ensure!(!Self::is_recovery_expired(&recovery), Error::<T>::RecoveryExpired);
+			ensure!(recovery.new_account_id == new_account_id, Error::<T>::RecoveryAccountMismatch);
+			ensure!(!recovery.approvals.contains(&who), Error::<T>::AlreadyApproved);
+			// Approvals are bounded the same way as guardians
+			recovery.approvals.try_push(who.clone()).map_err(|_| Error::<T>::NotGuardian)?;
+
+			Self::deposit_event(Event::<T>::RecoveryApproved {
+				lost_account_id: lost_account_id.clone(),
+				guardian: who,
+			});
+
+			if (recovery.approvals.len() as u32) < recovery_config.threshold {
+				ActiveRecoveries::<T>::insert(&lost_account_id, recovery);
+				return Ok(())
+			}
+
+			ensure!(
+				!IdentityOf::<T>::contains_key(&recovery.new_account_id),
+				Error::<T>::AlreadyRegistered
+			);
+			Self::move_identity(&lost_account_id, &recovery.new_account_id)?;
+
+			Self::deposit_event(Event::<T>::AccountRecovered {
+				old_account_id: lost_account_id,
+				new_account_id: recovery.new_account_id,
+			});
+
+			Ok(())
+		}
+
+		/// Cancel active recovery of `lost_account_id`, e.g. initiated by wrong account.
+		/// Can be called by the lost account itself or by any of its guardians
+		#[pallet::call_index(21)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn cancel_recovery(
+			origin: OriginFor<T>,
+			lost_account_id: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			if who != lost_account_id {
+				let is_guardian = RecoveryConfigs::<T>::get(&lost_account_id)
+					.map_or(false, |recovery_config| recovery_config.guardians.contains(&who));
+				ensure!(is_guardian, Error::<T>::NotGuardian);
+			}
+
+			ActiveRecoveries::<T>::take(&lost_account_id).ok_or(Error::<T>::RecoveryNotActive)?;
+
+			Self::deposit_event(Event::<T>::RecoveryCancelled { lost_account_id });
+
+			Ok(())
+		}
 	}
 }
 
@@ -448,6 +1100,225 @@ impl<T: Config> Pallet<T> {
 		IdentityOf::<T>::get(account_id).and_then(|identity| identity.registration_time)
 	}
 
+	/// Whether the user signed up in the current block
+	pub fn is_just_registered(account_id: &T::AccountId) -> bool {
+		Self::get_registration_time(account_id)
+			.map(|registration_time| registration_time >= pallet_timestamp::Pallet::<T>::now())
+			.unwrap_or_default()
+	}
+
+	/// Register new user or migrate existing one to `account_id`
+	/// if phone number is attested by phone verifiers
+	fn do_new_user(
+		who: T::AccountId,
+		attestations: &[(T::PublicKey, T::Signature)],
+		evidence_version: EvidenceVersion<BlockNumberFor<T>>,
+		account_id: T::AccountId,
+		username: T::Username,
+		phone_number_hash: T::PhoneNumberHash,
+	) -> DispatchResult {
+		ensure!(who == account_id, Error::<T>::AccountIdMismatch);
+
+		// Cast username to lowercase
+		let username = username.normalize();
+
+		let verifier_account_id = Self::check_attestations(
+			attestations,
+			evidence_version,
+			&account_id,
+			&username,
+			&phone_number_hash,
+		)?;
+
+		match Self::verify(&account_id, &username, &phone_number_hash) {
+			VerificationResult::Valid =>
+				Self::register_user(verifier_account_id, account_id, username, phone_number_hash),
+			VerificationResult::Migration =>
+				Self::migrate_user(verifier_account_id, account_id, phone_number_hash),
+			VerificationResult::AccountIdExists => Err(Error::<T>::AlreadyRegistered.into()),
+			VerificationResult::UsernameExists => Err(Error::<T>::UserNameTaken.into()),
+			VerificationResult::UsernameReserved => Err(Error::<T>::UsernameReserved.into()),
+			VerificationResult::InvalidUsername => Err(Error::<T>::InvalidUsername.into()),
+		}
+	}
+
+	/// Update `Username` and/or `PhoneNumberHash` of `who`. Phone number change
+	/// should be attested by phone verifiers
+	fn do_update_user(
+		who: T::AccountId,
+		attestations: &[(T::PublicKey, T::Signature)],
+		evidence_version: EvidenceVersion<BlockNumberFor<T>>,
+		username: Option<T::Username>,
+		phone_number_hash: Option<T::PhoneNumberHash>,
+	) -> DispatchResult {
+		ensure!(username.is_some() || phone_number_hash.is_some(), Error::<T>::InvalidArguments);
+		ensure!(IdentityOf::<T>::contains_key(&who), Error::<T>::NotFound);
+		// Safety: because of check above unwrap do not panics
+		let mut identity = IdentityOf::<T>::get(&who).unwrap();
+		let old_username = identity.username.clone();
+		let old_phone_number_hash = identity.phone_number_hash.clone();
+		let mut new_username = None;
+
+		if let Some(username) = username.clone() {
+			// Cast username to lowercase
+			let username = username.normalize();
+
+			ensure!(identity.username != username, Error::<T>::InvalidArguments);
+			ensure!(T::UsernameValidator::is_valid(&username), Error::<T>::InvalidUsername);
+			// Check username for uniqueness
+			ensure!(!UsernameFor::<T>::contains_key(&username), Error::<T>::UserNameTaken);
+			ensure!(!Self::is_username_reserved(&username, &who), Error::<T>::UsernameReserved);
+			// Remove old `Username` <-> `AccountId` relation
+			UsernameFor::<T>::remove(&identity.username);
+			Self::release_username(identity.username, &who);
+			UsernameFor::<T>::insert(&username, &who);
+			ReleasedUsernames::<T>::remove(&username);
+			// Set new username
+			identity.username = username.clone();
+			new_username = Some(username);
+		}
+
+		if let Some(phone_number_hash) = phone_number_hash.clone() {
+			ensure!(!attestations.is_empty(), Error::<T>::InvalidArguments);
+			let verifier_account_id = Self::check_attestations(
+				attestations,
+				evidence_version,
+				&who,
+				&identity.username,
+				&phone_number_hash,
+			)?;
+
+			ensure!(identity.phone_number_hash != phone_number_hash, Error::<T>::InvalidArguments);
+			// Check phone number for uniqueness
+			ensure!(
+				!PhoneNumberFor::<T>::contains_key(&phone_number_hash),
+				Error::<T>::PhoneNumberTaken
+			);
+			// Remove old `PhoneNumber` <-> `AccountId` relation
+			PhoneNumberFor::<T>::remove(identity.phone_number_hash);
+			PhoneNumberFor::<T>::insert(&phone_number_hash, &who);
+			VerifiedBy::<T>::insert(&who, verifier_account_id);
+			// Set new phone number
+			identity.phone_number_hash = phone_number_hash;
+		}
+
+		// Save identity changes
+		IdentityOf::<T>::insert(&who, &identity);
+
+		// Transaction indexing pallet will index the transaction and move phone number history
+		T::Hooks::on_update_user(
+			who.clone(),
+			None,
+			old_username,
+			new_username,
+			old_phone_number_hash,
+			phone_number_hash.clone(),
+		)?;
+
+		Self::deposit_event(Event::<T>::AccountUpdated {
+			account_id: who,
+			username: identity.username,
+			new_username: username,
+			phone_number_hash: identity.phone_number_hash,
+			new_phone_number_hash: phone_number_hash,
+		});
+
+		Ok(())
+	}
+
+	/// Whether phone verifier is suspended
+	pub fn is_suspended(verifier: &T::AccountId) -> bool {
+		PhoneVerifierInfo::<T>::get(verifier)
+			.map(|info| info.suspended)
+			.unwrap_or_default()
+	}
+
+	/// Number of phone verifiers which aren't suspended
+	fn active_verifiers_count() -> u32 {
+		PhoneVerifiers::<T>::get()
+			.iter()
+			.filter(|verifier| !Self::is_suspended(verifier))
+			.count() as u32
+	}
+
+	/// Set `suspended` flag of the phone verifier
+	fn set_suspended(verifier: &T::AccountId, suspended: bool) -> DispatchResult {
+		ensure!(PhoneVerifiers::<T>::get().contains(verifier), Error::<T>::NotVerifier);
+		PhoneVerifierInfo::<T>::mutate(verifier, |info| {
+			info.get_or_insert_with(|| VerifierInfo {
+				name: Default::default(),
+				url: Default::default(),
+				region: Default::default(),
+				suspended,
+			})
+			.suspended = suspended;
+		});
+
+		Ok(())
+	}
+
+	/// Check that phone number is attested by at least `VerificationThreshold`
+	/// distinct phone verifiers
+	///
+	/// # Returns
+	/// `AccountId` of the first phone verifier, which registration is attributed to
+	fn check_attestations(
+		attestations: &[(T::PublicKey, T::Signature)],
+		evidence_version: EvidenceVersion<BlockNumberFor<T>>,
+		account_id: &T::AccountId,
+		username: &T::Username,
+		phone_number_hash: &T::PhoneNumberHash,
+	) -> Result<T::AccountId, DispatchError> {
+		ensure!(
+			evidence_version.version() >= MinimumEvidenceVersion::<T>::get(),
+			Error::<T>::EvidenceVersionTooOld
+		);
+		if let EvidenceVersion::V2 { nonce, expires_at } = evidence_version {
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= expires_at,
+				Error::<T>::EvidenceExpired
+			);
+			ensure!(
+				!UsedEvidenceNonces::<T>::contains_key(phone_number_hash, nonce),
+				Error::<T>::EvidenceAlreadyUsed
+			);
+		}
+
+		let phone_verifiers = PhoneVerifiers::<T>::get();
+		let mut attesters: Vec<T::AccountId> = Vec::with_capacity(attestations.len());
+
+		for (verifier_public_key, verifier_signature) in attestations {
+			let verifier_account_id = verifier_public_key.clone().into();
+			// Check verification
+			ensure!(phone_verifiers.contains(&verifier_account_id), Error::<T>::NotVerifier);
+			ensure!(!Self::is_suspended(&verifier_account_id), Error::<T>::VerifierSuspended);
+			ensure!(!attesters.contains(&verifier_account_id), Error::<T>::DuplicateAttestation);
+			ensure!(
+				Self::verify_signature(
+					verifier_public_key.clone(),
+					verifier_signature.clone(),
+					&evidence_version,
+					account_id.clone(),
+					username.clone(),
+					phone_number_hash.clone()
+				),
+				Error::<T>::InvalidSignature
+			);
+
+			attesters.push(verifier_account_id);
+		}
+
+		let threshold = VerificationThreshold::<T>::get().max(1);
+		ensure!(attesters.len() as u32 >= threshold, Error::<T>::NotEnoughAttestations);
+
+		if let EvidenceVersion::V2 { nonce, .. } = evidence_version {
+			UsedEvidenceNonces::<T>::insert(phone_number_hash, nonce, ());
+		}
+
+		// Safety: threshold is at least 1, so `attesters` is not empty
+		Ok(attesters.swap_remove(0))
+	}
+
 	/// Perform validation for input parameters of `new_user` tx
 	pub fn verify(
 		account_id: &T::AccountId,
@@ -478,9 +1349,62 @@ impl<T: Config> Pallet<T> {
 			return VerificationResult::UsernameExists
 		}
 
+		if !T::UsernameValidator::is_valid(&username) {
+			return VerificationResult::InvalidUsername
+		}
+
+		if Self::is_username_reserved(&username, account_id) {
+			return VerificationResult::UsernameReserved
+		}
+
 		VerificationResult::Valid
 	}
 
+	/// Whether `username` is reserved or released by other account less than
+	/// `UsernameReservationPeriod` ago. `username` should be normalized
+	pub fn is_username_reserved(username: &T::Username, account_id: &T::AccountId) -> bool {
+		if ReservedUsernames::<T>::contains_key(username) {
+			return true
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		ReleasedUsernames::<T>::get(username)
+			.map(|(owner, reserved_until)| &owner != account_id && now < reserved_until)
+			.unwrap_or_default()
+	}
+
+	/// Keep released `username` for the `owner` during `UsernameReservationPeriod`
+	fn release_username(username: T::Username, owner: &T::AccountId) {
+		let period = T::UsernameReservationPeriod::get();
+		if period.is_zero() {
+			return
+		}
+
+		let reserved_until = frame_system::Pallet::<T>::block_number().saturating_add(period);
+		ReleasedUsernameExpirations::<T>::insert(reserved_until, &username, ());
+		ReleasedUsernames::<T>::insert(username, (owner, reserved_until));
+	}
+
+	/// Remove `ReleasedUsernames` which reservation expires at `now`
+	///
+	/// # Returns
+	/// Number of checked usernames
+	fn prune_released_usernames(now: BlockNumberFor<T>) -> u64 {
+		// Drain first to do not get undefined behavior from storage while
+		// simultaneously drain and remove
+		let usernames: Vec<_> = ReleasedUsernameExpirations::<T>::drain_prefix(now).collect();
+		usernames.iter().for_each(|(username, _)| {
+			// Username could be taken or released again since then
+			if ReleasedUsernames::<T>::get(username)
+				.map_or(false, |(_, reserved_until)| reserved_until <= now)
+			{
+				ReleasedUsernames::<T>::remove(username);
+			}
+		});
+
+		usernames.len() as u64
+	}
+
 	/// Checks that signature match passed data
 	///
 	/// # Returns
@@ -489,19 +1413,39 @@ impl<T: Config> Pallet<T> {
 	pub fn verify_signature(
 		verifier_public_key: T::PublicKey,
 		verifier_signature: T::Signature,
+		evidence_version: &EvidenceVersion<BlockNumberFor<T>>,
 		account_id: T::AccountId,
 		username: T::Username,
 		phone_number_hash: T::PhoneNumberHash,
 	) -> bool {
-		let data = VerificationEvidence {
-			verifier_public_key: verifier_public_key.clone(),
-			account_id,
-			username,
-			phone_number_hash,
-		}
-		.encode();
+		let evidence = match evidence_version {
+			EvidenceVersion::V1 => VersionedVerificationEvidence::V1(VerificationEvidence {
+				verifier_public_key: verifier_public_key.clone(),
+				account_id,
+				username,
+				phone_number_hash,
+			}),
+			EvidenceVersion::V2 { nonce, expires_at } =>
+				VersionedVerificationEvidence::V2(VerificationEvidenceV2 {
+					verifier_public_key: verifier_public_key.clone(),
+					account_id,
+					username,
+					phone_number_hash,
+					nonce: *nonce,
+					expires_at: *expires_at,
+					genesis_hash: frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero()),
+				}),
+		};
 
-		verifier_signature.verify(&*data, &verifier_public_key)
+		verifier_signature.verify(&*evidence.signing_payload(), &verifier_public_key)
+	}
+
+	/// Save `phone_verifier` of the `account_id` and count its registrations
+	fn note_verification(phone_verifier: &T::AccountId, account_id: &T::AccountId) {
+		VerifiedBy::<T>::insert(account_id, phone_verifier);
+		VerifierRegistrations::<T>::mutate(phone_verifier, |count| {
+			*count = count.saturating_add(1)
+		});
 	}
 
 	/// Add information about new user into storage, call `on_new_user` hook and deposit event
@@ -512,6 +1456,7 @@ impl<T: Config> Pallet<T> {
 		phone_number_hash: T::PhoneNumberHash,
 	) -> DispatchResult {
 		UsernameFor::<T>::insert(&username, account_id.clone());
+		ReleasedUsernames::<T>::remove(&username);
 		PhoneNumberFor::<T>::insert(&phone_number_hash, account_id.clone());
 		IdentityOf::<T>::insert(
 			&account_id,
@@ -521,6 +1466,7 @@ impl<T: Config> Pallet<T> {
 				registration_time: None,
 			},
 		);
+		Self::note_verification(&phone_verifier, &account_id);
 
 		T::Hooks::on_new_user(
 			phone_verifier.clone(),
@@ -539,43 +1485,44 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	/// Migrate user data to new `AccountId` and deposit event
-	pub(crate) fn migrate_user(
-		phone_verifier: T::AccountId,
-		new_account_id: T::AccountId,
-		phone_number_hash: T::PhoneNumberHash,
-	) -> DispatchResult {
-		// If such phone number exists migrate those account
-		// balance, trait score, etc to this new account
-
-		// Remove old account date
-		// Save unwrap because of check above
-		let old_account_id = PhoneNumberFor::<T>::take(&phone_number_hash).unwrap();
-		let identity = IdentityOf::<T>::take(&old_account_id).unwrap();
-		UsernameFor::<T>::remove(&identity.username);
+	/// Whether recovery wasn't completed during `RecoveryExpiration`
+	fn is_recovery_expired(recovery: &ActiveRecoveryOf<T>) -> bool {
+		frame_system::Pallet::<T>::block_number() >
+			recovery.initiated_at.saturating_add(T::RecoveryExpiration::get())
+	}
 
-		// Save old nickname and new `AccountId`
-		UsernameFor::<T>::insert(&identity.username, new_account_id.clone());
-		PhoneNumberFor::<T>::insert(&phone_number_hash, new_account_id.clone());
-		IdentityOf::<T>::insert(
-			&new_account_id,
-			IdentityStore {
-				username: identity.username.clone(),
-				phone_number_hash,
-				registration_time: identity.registration_time,
-			},
-		);
+	/// Move identity, balance and data of other pallets from `old_account_id`
+	/// to `new_account_id`
+	fn move_identity(
+		old_account_id: &T::AccountId,
+		new_account_id: &T::AccountId,
+	) -> DispatchResult {
+		// Remove old account data
+		let identity = IdentityOf::<T>::take(old_account_id).ok_or(Error::<T>::NotFound)?;
+
+		// Save old nickname and phone number with new `AccountId`
+		UsernameFor::<T>::insert(&identity.username, new_account_id);
+		PhoneNumberFor::<T>::insert(&identity.phone_number_hash, new_account_id);
+		IdentityOf::<T>::insert(new_account_id, &identity);
+		if let Some(phone_verifier) = VerifiedBy::<T>::take(old_account_id) {
+			VerifiedBy::<T>::insert(new_account_id, phone_verifier);
+		}
+		if let Some(recovery_config) = RecoveryConfigs::<T>::take(old_account_id) {
+			RecoveryConfigs::<T>::insert(new_account_id, recovery_config);
+		}
+		ActiveRecoveries::<T>::remove(old_account_id);
 
 		// Transfer balance
-		let amount = T::Currency::free_balance(&old_account_id);
+		let amount = T::Currency::free_balance(old_account_id);
 		T::Currency::transfer(
-			&old_account_id,
-			&new_account_id,
+			old_account_id,
+			new_account_id,
 			amount,
 			ExistenceRequirement::AllowDeath,
 		)?;
 
 		// Appreciation pallet will migrate traits score and communities membership
+		// Reward pallet will migrate reward info
 		// Transaction indexing pallet will migrate transactions
 		T::Hooks::on_update_user(
 			old_account_id.clone(),
@@ -584,7 +1531,22 @@ impl<T: Config> Pallet<T> {
 			None,
 			identity.phone_number_hash,
 			None,
-		)?;
+		)
+	}
+
+	/// Migrate user data to new `AccountId` and deposit event
+	pub(crate) fn migrate_user(
+		phone_verifier: T::AccountId,
+		new_account_id: T::AccountId,
+		phone_number_hash: T::PhoneNumberHash,
+	) -> DispatchResult {
+		// If such phone number exists migrate those account
+		// balance, trait score, etc to this new account
+
+		// Save unwrap because of check above
+		let old_account_id = PhoneNumberFor::<T>::get(&phone_number_hash).unwrap();
+		Self::move_identity(&old_account_id, &new_account_id)?;
+		Self::note_verification(&phone_verifier, &new_account_id);
 
 		Self::deposit_event(Event::<T>::AccountMigrated {
 			phone_verifier,
@@ -0,0 +1,16 @@
+/// Validate username before it's taken by user
+pub trait UsernameValidator<Username> {
+	/// Check normalized `username`
+	///
+	/// # Returns
+	/// `true` - if username can be taken
+	/// `false` - otherwise
+	fn is_valid(username: &Username) -> bool;
+}
+
+/// Accept any username
+impl<Username> UsernameValidator<Username> for () {
+	fn is_valid(_username: &Username) -> bool {
+		true
+	}
+}
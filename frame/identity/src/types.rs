@@ -1,5 +1,9 @@
 use codec::{Codec, Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
+};
 use scale_info::TypeInfo;
+use sp_common::BoundedString;
 
 #[derive(Clone, Encode, Decode, Eq, MaxEncodedLen, PartialEq, Debug, TypeInfo)]
 pub struct IdentityStore<Username, PhoneNumberHash, Moment>
@@ -13,6 +17,62 @@ where
 	pub registration_time: Option<Moment>,
 }
 
+/// Phone verifier metadata
+#[derive(
+	CloneNoBound, Encode, Decode, MaxEncodedLen, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo,
+)]
+#[codec(mel_bound())]
+#[scale_info(skip_type_params(NameLimit, UrlLimit))]
+pub struct VerifierInfo<NameLimit: Get<u32>, UrlLimit: Get<u32>> {
+	/// Display name
+	pub name: BoundedString<NameLimit>,
+	/// Contact URL
+	pub url: BoundedString<UrlLimit>,
+	/// Region where phone verifier operates
+	pub region: BoundedString<NameLimit>,
+	/// Suspended phone verifier can't attest phone numbers
+	pub suspended: bool,
+}
+
+/// Guardians who can approve recovery of the account
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+#[scale_info(skip_type_params(MaxGuardians))]
+pub struct RecoveryConfig<AccountId, MaxGuardians: Get<u32>> {
+	pub guardians: BoundedVec<AccountId, MaxGuardians>,
+	/// Number of guardians approvals required to recover the account
+	pub threshold: u32,
+}
+
+/// Recovery of the lost account to `new_account_id`
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
+#[scale_info(skip_type_params(MaxGuardians))]
+pub struct ActiveRecovery<AccountId, BlockNumber, MaxGuardians: Get<u32>> {
+	pub new_account_id: AccountId,
+	/// Guardians who approved the recovery
+	pub approvals: BoundedVec<AccountId, MaxGuardians>,
+	pub initiated_at: BlockNumber,
+}
+
+/// Version of `VerificationEvidence` signed by phone verifiers
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum EvidenceVersion<BlockNumber> {
+	/// Legacy evidence without replay protection
+	V1,
+	/// Evidence with `nonce`, expiration block number and genesis hash
+	V2 { nonce: u64, expires_at: BlockNumber },
+}
+
+impl<BlockNumber> EvidenceVersion<BlockNumber> {
+	pub fn version(&self) -> u8 {
+		match self {
+			Self::V1 => 1,
+			Self::V2 { .. } => 2,
+		}
+	}
+}
+
 pub enum VerificationResult {
 	/// Parameters are valid
 	Valid,
@@ -22,4 +82,8 @@ pub enum VerificationResult {
 	AccountIdExists,
 	/// This `Username` belong to another user
 	UsernameExists,
+	/// This `Username` is reserved or recently released by another user
+	UsernameReserved,
+	/// This `Username` is rejected by `UsernameValidator`
+	InvalidUsername,
 }
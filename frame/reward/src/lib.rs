@@ -612,6 +612,12 @@ impl<T: Config> Pallet<T> {
 			reward_info.appreciation_count += 1;
 		});
 	}
+
+	pub(crate) fn note_undo_appreciation(account_id: T::AccountId) {
+		AccountRewardInfo::<T>::mutate(&account_id, |reward_info| {
+			reward_info.appreciation_count = reward_info.appreciation_count.saturating_sub(1);
+		});
+	}
 }
 
 impl<T: Config> KarmaHooks<T::AccountId, T::Balance, T::Username, T::PhoneNumberHash>
@@ -672,6 +678,19 @@ impl<T: Config> KarmaHooks<T::AccountId, T::Balance, T::Username, T::PhoneNumber
 		Ok(())
 	}
 
+	fn on_undo_appreciation(
+		payer: T::AccountId,
+		payee: T::AccountId,
+		_amount: T::Balance,
+		_community_id: CommunityId,
+		_char_trait_id: CharTraitId,
+	) -> DispatchResult {
+		Self::note_undo_appreciation(payer);
+		Self::note_undo_appreciation(payee);
+
+		Ok(())
+	}
+
 	fn on_referral(who: T::AccountId, _whom: T::AccountId) -> DispatchResult {
 		let reward = Self::get_current_referral_reward_amount();
 		Self::issue_referral_reward(&who, reward)?;
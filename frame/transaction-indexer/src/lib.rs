@@ -1,6 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::dispatch::DispatchResult;
+use frame_support::{dispatch::DispatchResult, traits::Get, weights::Weight};
 pub use pallet::*;
 use sp_common::{
 	traits::IdentityProvider,
@@ -45,6 +45,18 @@ pub mod pallet {
 	pub type PhoneNumberHashTransactions<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::PhoneNumberHash, Vec<(BlockNumberFor<T>, u32)>>;
 
+	/// Last transaction indexed for the account. Same transaction may be indexed
+	/// multiple times, e.g. `batch_appreciation` to the same account, so it's checked
+	/// without decoding the whole `AccountTransactions` history
+	#[pallet::storage]
+	pub type LastAccountTransaction<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (BlockNumberFor<T>, u32)>;
+
+	/// Last transaction indexed for the phone number hash, same as `LastAccountTransaction`
+	#[pallet::storage]
+	pub type LastPhoneNumberHashTransaction<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PhoneNumberHash, (BlockNumberFor<T>, u32)>;
+
 	#[pallet::storage]
 	pub type TransactionsCount<T: Config> = StorageValue<_, u64, ValueQuery>;
 
@@ -89,7 +101,11 @@ impl<T: Config> Pallet<T> {
 		let hash = T::Hashing::hash(&extrinsic_data);
 
 		TxHashes::<T>::insert(hash, (block_number, extrinsic_index));
-		AccountTransactions::<T>::append(account_id, (block_number, extrinsic_index));
+		let transaction = (block_number, extrinsic_index);
+		if LastAccountTransaction::<T>::get(&account_id) != Some(transaction) {
+			LastAccountTransaction::<T>::insert(&account_id, transaction);
+			AccountTransactions::<T>::append(account_id, transaction);
+		}
 
 		Ok(())
 	}
@@ -104,13 +120,36 @@ impl<T: Config> Pallet<T> {
 		let hash = T::Hashing::hash(&extrinsic_data);
 
 		TxHashes::<T>::insert(hash, (block_number, extrinsic_index));
-		PhoneNumberHashTransactions::<T>::append(
-			phone_number_hash,
-			(block_number, extrinsic_index),
-		);
+		let transaction = (block_number, extrinsic_index);
+		if LastPhoneNumberHashTransaction::<T>::get(&phone_number_hash) != Some(transaction) {
+			LastPhoneNumberHashTransaction::<T>::insert(&phone_number_hash, transaction);
+			PhoneNumberHashTransactions::<T>::append(phone_number_hash, transaction);
+		}
 
 		Ok(())
 	}
+
+	/// Move transactions history of the user to the new phone number hash
+	fn move_phone_number_hash_transactions(
+		phone_number_hash: T::PhoneNumberHash,
+		new_phone_number_hash: T::PhoneNumberHash,
+	) {
+		let last_transaction = LastPhoneNumberHashTransaction::<T>::take(&phone_number_hash)
+			.max(LastPhoneNumberHashTransaction::<T>::get(&new_phone_number_hash));
+		if let Some(last_transaction) = last_transaction {
+			LastPhoneNumberHashTransaction::<T>::insert(&new_phone_number_hash, last_transaction);
+		}
+
+		let transactions = PhoneNumberHashTransactions::<T>::take(phone_number_hash);
+		PhoneNumberHashTransactions::<T>::mutate(new_phone_number_hash, |new_transactions| {
+			let mut moved_transactions = transactions.unwrap_or_default();
+			// New phone number may have history, e.g. transactions before the user signed up
+			moved_transactions.extend(new_transactions.take().unwrap_or_default());
+			moved_transactions.sort();
+			moved_transactions.dedup();
+			*new_transactions = Some(moved_transactions);
+		});
+	}
 }
 
 impl<T: Config> sp_common::hooks::Hooks<T::AccountId, T::Balance, T::Username, T::PhoneNumberHash>
@@ -141,12 +180,22 @@ impl<T: Config> sp_common::hooks::Hooks<T::AccountId, T::Balance, T::Username, T
 			Self::index_transaction_by_account_id(new_account_id)?;
 		}
 
-		Self::index_transaction_by_phone_number_hash(phone_number_hash)?;
-		if let Some(new_phone_number_hash) = new_phone_number_hash {
-			Self::index_transaction_by_phone_number_hash(new_phone_number_hash)?;
+		match new_phone_number_hash {
+			Some(new_phone_number_hash) => {
+				Self::move_phone_number_hash_transactions(
+					phone_number_hash,
+					new_phone_number_hash.clone(),
+				);
+				Self::index_transaction_by_phone_number_hash(new_phone_number_hash)
+			},
+			None => Self::index_transaction_by_phone_number_hash(phone_number_hash),
 		}
+	}
 
-		Ok(())
+	fn on_update_user_weight() -> Weight {
+		// Transactions counter, indexing by account and phone number hash
+		// and moving phone number history
+		T::DbWeight::get().reads_writes(13, 11)
 	}
 
 	fn on_appreciation(
@@ -159,7 +208,7 @@ impl<T: Config> sp_common::hooks::Hooks<T::AccountId, T::Balance, T::Username, T
 		let payer_phone_number_hash = T::IdentityProvider::identity_by_id(&payer)
 			.ok_or(Error::<T>::NotFound)?
 			.phone_number_hash;
-		let payee_phone_number_hash = T::IdentityProvider::identity_by_id(&payer)
+		let payee_phone_number_hash = T::IdentityProvider::identity_by_id(&payee)
 			.ok_or(Error::<T>::NotFound)?
 			.phone_number_hash;
 
@@ -180,6 +229,29 @@ impl<T: Config> sp_common::hooks::Hooks<T::AccountId, T::Balance, T::Username, T
 		Ok(())
 	}
 
+	fn on_undo_appreciation(
+		payer: T::AccountId,
+		payee: T::AccountId,
+		_amount: T::Balance,
+		_community_id: CommunityId,
+		_char_trait_id: CharTraitId,
+	) -> DispatchResult {
+		let payer_phone_number_hash = T::IdentityProvider::identity_by_id(&payer)
+			.ok_or(Error::<T>::NotFound)?
+			.phone_number_hash;
+		let payee_phone_number_hash = T::IdentityProvider::identity_by_id(&payee)
+			.ok_or(Error::<T>::NotFound)?
+			.phone_number_hash;
+
+		Self::index_transaction_by_account_id(payer)?;
+		Self::index_transaction_by_account_id(payee)?;
+
+		Self::index_transaction_by_phone_number_hash(payer_phone_number_hash)?;
+		Self::index_transaction_by_phone_number_hash(payee_phone_number_hash)?;
+
+		Ok(())
+	}
+
 	fn on_set_admin(who: T::AccountId, new_admin: T::AccountId) -> DispatchResult {
 		let who_phone_number_hash = T::IdentityProvider::identity_by_id(&who)
 			.ok_or(Error::<T>::NotFound)?
@@ -201,7 +273,9 @@ impl<T: Config> sp_common::hooks::Hooks<T::AccountId, T::Balance, T::Username, T
 		phone_number_hash: T::PhoneNumberHash,
 	) -> DispatchResult {
 		AccountTransactions::<T>::remove(&account_id);
+		LastAccountTransaction::<T>::remove(&account_id);
 		PhoneNumberHashTransactions::<T>::remove(&phone_number_hash);
+		LastPhoneNumberHashTransaction::<T>::remove(&phone_number_hash);
 
 		Ok(())
 	}
@@ -1,12 +1,13 @@
 use crate::chain_spec::backup::backup_json::User;
-use karmachain_node_runtime::{AccountId, Balance, PhoneNumberHash, Username};
+use karmachain_node_runtime::{
+	AccountId, Balance, KarmaUsernameValidator, MinUsernameLength, NameLimit, PhoneNumberHash,
+	Username,
+};
 use pallet_appreciation::CommunityRole;
+use pallet_identity::traits::UsernameValidator;
 use scale_info::prelude::string::String;
-use sp_common::{
-	traits::MaybeNormalized,
-	types::{CharTraitId, CommunityId, Score},
-};
-use sp_core::hashing::blake2_512;
+use sp_common::types::{CharTraitId, CommunityId, Score};
+use sp_core::{hashing::blake2_512, Get};
 
 /// Contains well prepared genesis configuration for the chain based on backup file
 pub struct BackupGenesisConfig {
@@ -57,7 +58,7 @@ impl BackupGenesisConfig {
 
 			// Make username unique
 			let mut index = 0;
-			let mut username = info.user_name.trim().to_lowercase().to_string();
+			let mut username = Self::valid_username(&info.user_name);
 			while identities.iter().any(|(_, u, _)| *u == username) {
 				if index != 0 {
 					username = username[..username.len() - 2].to_string();
@@ -66,12 +67,11 @@ impl BackupGenesisConfig {
 				index += 1;
 			}
 
-			let username = Username::try_from(username);
-			// Skip invalid usernames
-			if username.is_err() {
-				continue
-			}
-			let username = username.unwrap().normalize();
+			// Users are never dropped, because their balance and scores are kept
+			let username = Username::try_from(username.as_str())
+				.ok()
+				.filter(KarmaUsernameValidator::is_valid)
+				.ok_or_else(|| format!("Can't make username {} valid", info.user_name))?;
 
 			identities.push((account_id, username, phone_number_hash));
 		}
@@ -113,6 +113,36 @@ impl BackupGenesisConfig {
 		Ok(Self { endowed_accounts, identities, community_membership, trait_scores, treasury })
 	}
 
+	/// Convert username from the backup to the form accepted by `KarmaUsernameValidator`.
+	/// Unsupported symbols are replaced with `_`, separators are not repeated and not
+	/// placed at the edges, too short username is extended with `user`
+	fn valid_username(username: &str) -> String {
+		let is_separator = |char: char| matches!(char, '_' | '.' | '-');
+
+		let mut valid = String::new();
+		for char in username.trim().to_lowercase().chars() {
+			if char.is_ascii_lowercase() || char.is_ascii_digit() {
+				valid.push(char);
+			} else if !valid.is_empty() && !valid.ends_with(is_separator) {
+				valid.push(if is_separator(char) { char } else { '_' });
+			}
+		}
+
+		// Leave space for the suffix which makes username unique
+		valid.truncate(NameLimit::get() as usize - 4);
+		while valid.ends_with(is_separator) {
+			valid.pop();
+		}
+		if valid.len() < MinUsernameLength::get() as usize {
+			if !valid.is_empty() {
+				valid.push('_');
+			}
+			valid.push_str("user");
+		}
+
+		valid
+	}
+
 	// Group all developer accounts by adding their balances to main developer account
 	fn group_dev_accounts(users: &mut Vec<User>) {
 		// Main developer account where all funds go
@@ -253,7 +253,15 @@ fn development_genesis(
 			trait_scores,
 			..Default::default()
 		},
-		identity: IdentityConfig { phone_verifiers, identities: identities.clone() },
+		identity: IdentityConfig {
+			phone_verifiers_info: phone_verifiers_info(&phone_verifiers),
+			phone_verifiers,
+			identities: identities.clone(),
+			reserved_usernames: reserved_usernames(),
+			verification_threshold: 1,
+			// New networks accept only evidence with replay protection
+			minimum_evidence_version: pallet_identity::LATEST_EVIDENCE_VERSION,
+		},
 		reward: RewardConfig {
 			accounts: identities.into_iter().map(|(account_id, _, _)| account_id).collect(),
 			offchain_accounts,
@@ -1,4 +1,4 @@
-use karmachain_node_runtime::{AccountId, RuntimeGenesisConfig, Signature};
+use karmachain_node_runtime::{AccountId, RuntimeGenesisConfig, Signature, Username};
 use sp_core::{Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
@@ -24,3 +24,29 @@ where
 {
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
+
+/// Usernames which can't be taken by users
+pub fn reserved_usernames() -> Vec<Username> {
+	["admin", "karma", "karmacoin", "karmachain", "support", "sudo"]
+		.into_iter()
+		.map(|username| Username::try_from(username).expect("static values are valid; qed"))
+		.collect()
+}
+
+/// Default name, contact URL and region of phone verifiers
+pub fn phone_verifiers_info(
+	phone_verifiers: &[AccountId],
+) -> Vec<(AccountId, String, String, String)> {
+	phone_verifiers
+		.iter()
+		.enumerate()
+		.map(|(index, account_id)| {
+			(
+				account_id.clone(),
+				format!("Phone verifier {}", index + 1),
+				String::new(),
+				String::new(),
+			)
+		})
+		.collect()
+}
@@ -1,5 +1,5 @@
 use crate::types::{CharTraitId, CommunityId};
-use frame_support::dispatch::DispatchResult;
+use frame_support::{dispatch::DispatchResult, weights::Weight};
 
 pub trait Hooks<AccountId, Balance, Username, PhoneNumberHash> {
 	/// New user registered via `new_user` transactions. Implement to have something happen.
@@ -53,6 +53,13 @@ pub trait Hooks<AccountId, Balance, Username, PhoneNumberHash> {
 		Ok(())
 	}
 
+	/// Weight of `on_update_user` hook for the user who keeps the account, i.e. only username
+	/// or phone number is changed. Implement if the hook does storage operations, so they are
+	/// charged with `update_user` transactions.
+	fn on_update_user_weight() -> Weight {
+		Weight::zero()
+	}
+
 	/// User appreciated via `appreciation` transactions. Implement to have something happen.
 	/// This hook called after all checks performed and all values wrote to the storage.
 	///
@@ -77,6 +84,30 @@ pub trait Hooks<AccountId, Balance, Username, PhoneNumberHash> {
 		Ok(())
 	}
 
+	/// Appreciation undone via `undo_appreciation` transactions. Implement to have something
+	/// happen. This hook called after all checks performed and all values wrote to the storage.
+	///
+	/// # Arguments
+	///
+	/// * `payer` - `AccountId` of account who undo appreciation
+	/// * `payee` - `AccountId` of account who returned tokens
+	/// * `amount` - amount of tokens returned to the payer
+	/// * `community_id` - community of the undone appreciation
+	/// * `char_trait_id` - trait of the undone appreciation
+	///
+	/// # Returns
+	///
+	/// `Err` cause to abort transaction and revert state
+	fn on_undo_appreciation(
+		_payer: AccountId,
+		_payee: AccountId,
+		_amount: Balance,
+		_community_id: CommunityId,
+		_char_trait_id: CharTraitId,
+	) -> DispatchResult {
+		Ok(())
+	}
+
 	/// New admin set for community via `set_admin` transactions. Implement to have something
 	/// happen. This hook called after all checks performed and all values wrote to the storage.
 	///
@@ -172,6 +203,10 @@ where
 		)
 	}
 
+	fn on_update_user_weight() -> Weight {
+		H1::on_update_user_weight().saturating_add(H2::on_update_user_weight())
+	}
+
 	fn on_appreciation(
 		payer: AccountId,
 		payee: AccountId,
@@ -189,6 +224,23 @@ where
 		H2::on_appreciation(payer, payee, amount, community_id, char_trait_id)
 	}
 
+	fn on_undo_appreciation(
+		payer: AccountId,
+		payee: AccountId,
+		amount: Balance,
+		community_id: CommunityId,
+		char_trait_id: CharTraitId,
+	) -> DispatchResult {
+		H1::on_undo_appreciation(
+			payer.clone(),
+			payee.clone(),
+			amount.clone(),
+			community_id,
+			char_trait_id,
+		)?;
+		H2::on_undo_appreciation(payer, payee, amount, community_id, char_trait_id)
+	}
+
 	fn on_set_admin(who: AccountId, new_admin: AccountId) -> DispatchResult {
 		H1::on_set_admin(who.clone(), new_admin.clone())?;
 		H2::on_set_admin(who, new_admin)
@@ -39,6 +39,11 @@ where
 	) -> Option<IdentityInfo<AccountId, Username, PhoneNumberHash>>;
 }
 
+pub trait CommunityMembershipProvider<AccountId> {
+	/// Whether `who` and `other` are members of at least one common community
+	fn share_community(who: &AccountId, other: &AccountId) -> bool;
+}
+
 pub trait ScoreProvider<AccountId> {
 	fn score_of(account_id: &AccountId) -> Score;
 }
@@ -19,6 +19,14 @@ pub struct CharTrait {
 pub struct PhoneVerifier<AccountId> {
 	pub account_id: AccountId,
 	pub name: String,
+	/// Contact URL
+	pub url: String,
+	/// Region where phone verifier operates
+	pub region: String,
+	/// Suspended phone verifier can't attest phone numbers
+	pub suspended: bool,
+	/// Number of users registered or migrated by phone verifier
+	pub registrations_count: u64,
 }
 
 #[derive(Encode, Decode, TypeInfo)]
@@ -16,6 +16,16 @@ pub struct TraitScore {
 	pub community_id: u32,
 }
 
+#[derive(Encode, Decode, TypeInfo, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct Badge {
+	pub community_id: u32,
+	pub trait_id: u32,
+	pub level: u8,
+	/// Block number at which the badge was earned
+	pub block_number: u32,
+}
+
 #[derive(Encode, Decode, TypeInfo, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 pub struct CommunityMembership {
@@ -37,6 +47,17 @@ pub struct UserInfo<AccountId> {
 	pub karma_score: u32,
 	pub community_membership: Vec<CommunityMembership>,
 	pub metadata: Option<Vec<u8>>,
+	pub badges: Vec<Badge>,
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct CommunityTreasury<AccountId> {
+	pub community_id: u32,
+	pub account_id: AccountId,
+	pub balance: u64,
+	/// Amount paid to the payee on each appreciation within the community
+	pub appreciation_bonus: Option<u64>,
 }
 
 #[derive(Encode, Decode, TypeInfo)]
@@ -48,4 +69,5 @@ pub struct Contact<AccountId> {
 	pub community_membership: Vec<CommunityMembership>,
 	pub trait_scores: Vec<TraitScore>,
 	pub metadata: Option<Vec<u8>>,
+	pub badges: Vec<Badge>,
 }
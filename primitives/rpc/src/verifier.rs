@@ -1,8 +1,10 @@
 use codec::{Decode, Encode};
-use scale_info::TypeInfo;
+use scale_info::{prelude::vec::Vec, TypeInfo};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
+/// Legacy evidence without replay protection. Signature stays valid forever,
+/// so it can be resubmitted, e.g. after the user is deleted
 #[derive(Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 pub struct VerificationEvidence<PublicKey, AccountId, Username, PhoneNumberHash> {
@@ -11,3 +13,60 @@ pub struct VerificationEvidence<PublicKey, AccountId, Username, PhoneNumberHash>
 	pub username: Username,
 	pub phone_number_hash: PhoneNumberHash,
 }
+
+/// Evidence with replay protection. `nonce` can be used only once per phone number,
+/// signature is valid till `expires_at` block and only on chain with `genesis_hash`
+#[derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct VerificationEvidenceV2<
+	PublicKey,
+	AccountId,
+	Username,
+	PhoneNumberHash,
+	BlockNumber,
+	Hash,
+> {
+	pub verifier_public_key: PublicKey,
+	pub account_id: AccountId,
+	pub username: Username,
+	pub phone_number_hash: PhoneNumberHash,
+	pub nonce: u64,
+	pub expires_at: BlockNumber,
+	pub genesis_hash: Hash,
+}
+
+/// Data signed by phone verifier. `V1` is signed as plain `VerificationEvidence`
+/// for compatibility with existing verifier services, newer versions are signed
+/// with version prefix
+#[derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum VersionedVerificationEvidence<
+	PublicKey,
+	AccountId,
+	Username,
+	PhoneNumberHash,
+	BlockNumber,
+	Hash,
+> {
+	V1(VerificationEvidence<PublicKey, AccountId, Username, PhoneNumberHash>),
+	V2(VerificationEvidenceV2<PublicKey, AccountId, Username, PhoneNumberHash, BlockNumber, Hash>),
+}
+
+impl<PublicKey, AccountId, Username, PhoneNumberHash, BlockNumber, Hash>
+	VersionedVerificationEvidence<PublicKey, AccountId, Username, PhoneNumberHash, BlockNumber, Hash>
+where
+	PublicKey: Encode,
+	AccountId: Encode,
+	Username: Encode,
+	PhoneNumberHash: Encode,
+	BlockNumber: Encode,
+	Hash: Encode,
+{
+	/// Bytes which phone verifier should sign
+	pub fn signing_payload(&self) -> Vec<u8> {
+		match self {
+			Self::V1(evidence) => evidence.encode(),
+			versioned => versioned.encode(),
+		}
+	}
+}
@@ -5,9 +5,10 @@ use pallet_nomination_pools::PoolId;
 use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, RuntimeDispatchInfo};
 use sp_common::{types::CommunityId, BoundedString};
 use sp_rpc::{
-	BlockchainStats, BondedPool, CharTrait, CommunityMembership, Contact, GenesisData,
-	NominationPoolsConfiguration, Nominations, PhoneVerifier, PoolMember, SignedTransaction,
-	SignedTransactionWithStatus, TraitScore, TransactionStatus, UserInfo, ValidatorPrefs,
+	Badge, BlockchainStats, BondedPool, CharTrait, CommunityMembership, CommunityTreasury, Contact,
+	GenesisData, NominationPoolsConfiguration, Nominations, PhoneVerifier, PoolMember,
+	SignedTransaction, SignedTransactionWithStatus, TraitScore, TransactionStatus, UserInfo,
+	ValidatorPrefs,
 };
 use sp_runtime::{generic::SignedBlock, traits::StaticLookup};
 
@@ -23,13 +24,19 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
 	state_version: 1,
 };
 
+/// Karma score of the user: sum of trait scores and a point for each community membership
+fn karma_score(trait_scores: &[TraitScore], community_membership: &[CommunityMembership]) -> u32 {
+	trait_scores.iter().map(|score| score.karma_score).sum::<u32>() +
+		community_membership.len() as u32
+}
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -336,7 +343,10 @@ impl_runtime_apis! {
 			// let treasury_account_id = todo!(); // TODO:
 			// let treasury_account_name = vec![]; // TODO:
 
-			let char_traits = Appreciation::char_traits()
+			let mut char_traits =
+				pallet_appreciation::CharTraits::<Runtime>::iter_values().collect::<Vec<_>>();
+			char_traits.sort_by_key(|char_trait| char_trait.id);
+			let char_traits = char_traits
 				.into_iter()
 				.map(|v| CharTrait {
 					id: v.id,
@@ -344,13 +354,7 @@ impl_runtime_apis! {
 					emoji: v.emoji.try_into().unwrap_or_default(),
 				})
 				.collect();
-			let verifiers = Identity::verifiers()
-				.into_iter()
-				.map(|v| PhoneVerifier {
-					account_id: v,
-					name: Default::default(), // TODO:
-				})
-				.collect();
+			let verifiers = Self::get_phone_verifiers();
 
 			GenesisData {
 				net_id,
@@ -378,8 +382,32 @@ impl_runtime_apis! {
 			}
 		}
 
-		fn get_char_traits(from_index: Option<u32>, limit: Option<u32>) -> Vec<CharTrait> {
-			pallet_appreciation::CharTraits::<Runtime>::get()
+		fn get_char_traits(
+			from_index: Option<u32>,
+			limit: Option<u32>,
+			community_id: Option<CommunityId>,
+		) -> Vec<CharTrait> {
+			let mut char_traits = match community_id {
+				Some(community_id) => {
+					let attached = pallet_appreciation::Communities::<Runtime>::get(community_id)
+						.map(|community| community.char_traits.into_inner())
+						.unwrap_or_default();
+
+					let custom = pallet_appreciation::CommunityCharTraits::<Runtime>::iter_prefix_values(
+						community_id,
+					);
+
+					attached
+						.into_iter()
+						.filter_map(pallet_appreciation::CharTraits::<Runtime>::get)
+						.chain(custom)
+						.collect::<Vec<_>>()
+				},
+				None => pallet_appreciation::CharTraits::<Runtime>::iter_values().collect::<Vec<_>>(),
+			};
+			char_traits.sort_by_key(|char_trait| char_trait.id);
+
+			char_traits
 				.into_iter()
 				.skip(from_index.unwrap_or(0) as usize)
 				.take(limit.unwrap_or(u32::MAX) as usize)
@@ -392,6 +420,29 @@ impl_runtime_apis! {
 				})
 				.collect()
 		}
+
+		fn get_phone_verifiers() -> Vec<PhoneVerifier<AccountId>> {
+			Identity::verifiers()
+				.into_iter()
+				.map(|account_id| {
+					let (name, url, region, suspended) =
+						pallet_identity::PhoneVerifierInfo::<Runtime>::get(&account_id)
+							.map(|info| {
+								(
+									info.name.try_into().unwrap_or_default(),
+									info.url.try_into().unwrap_or_default(),
+									info.region.try_into().unwrap_or_default(),
+									info.suspended,
+								)
+							})
+							.unwrap_or_default();
+					let registrations_count =
+						pallet_identity::VerifierRegistrations::<Runtime>::get(&account_id);
+
+					PhoneVerifier { account_id, name, url, region, suspended, registrations_count }
+				})
+				.collect()
+		}
 	}
 
 	impl runtime_api::events::EventProvider<Block, EventRecord<RuntimeEvent, Hash>> for Runtime {
@@ -431,8 +482,14 @@ impl_runtime_apis! {
 						community_id, karma_score, is_admin
 					})
 					.collect::<Vec<_>>();
-				let karma_score = trait_scores.iter().map(|score| score.karma_score).sum::<u32>() + community_membership.len() as u32;
+				let karma_score = karma_score(&trait_scores, &community_membership);
 				let metadata = Identity::metadata(&identity_info.account_id).map(Into::into);
+				let badges = Appreciation::badges_of(&identity_info.account_id)
+					.into_iter()
+					.map(|(community_id, trait_id, level, block_number)| Badge {
+						community_id, trait_id, level, block_number
+					})
+					.collect::<Vec<_>>();
 
 				UserInfo {
 					account_id: identity_info.account_id,
@@ -444,6 +501,7 @@ impl_runtime_apis! {
 					karma_score,
 					community_membership,
 					metadata: metadata,
+					badges,
 				}
 			})
 		}
@@ -498,6 +556,12 @@ impl_runtime_apis! {
 						})
 						.collect();
 					let metadata = Identity::metadata(&account_id).map(Into::into);
+					let badges: Vec<_> = Appreciation::badges_of(&account_id)
+						.into_iter()
+						.map(|(community_id, trait_id, level, block_number)| Badge {
+							community_id, trait_id, level, block_number
+						})
+						.collect();
 
 					Contact {
 						user_name: identity_store.username.try_into().unwrap_or_default(),
@@ -506,6 +570,7 @@ impl_runtime_apis! {
 						community_membership,
 						trait_scores,
 						metadata: metadata,
+						badges,
 					}
 				})
 				.collect()
@@ -516,10 +581,40 @@ impl_runtime_apis! {
 				.into_iter()
 				.map(|account_id| {
 					// Safety: if account participate in karma reward it must have identity
-					Self::get_user_info(AccountIdentity::AccountId(account_id)).unwrap()
+					let mut user_info = Self::get_user_info(AccountIdentity::AccountId(account_id.clone())).unwrap();
+					// Leader board is ranked by decayed karma score, so show it instead of lifetime one
+					user_info.trait_scores = Appreciation::decayed_trait_scores_of(&account_id)
+						.into_iter()
+						.map(|(community_id, trait_id, karma_score)| {
+							TraitScore {
+								trait_id, karma_score, community_id
+							}
+						})
+						.collect();
+					user_info.karma_score = karma_score(&user_info.trait_scores, &user_info.community_membership);
+
+					user_info
 				})
 				.collect()
 		}
+
+		fn get_community_treasury(community_id: CommunityId) -> Option<CommunityTreasury<AccountId>> {
+			if !pallet_appreciation::Communities::<Runtime>::contains_key(community_id) {
+				return None
+			}
+
+			let account_id = Appreciation::community_account_id(community_id);
+			let balance = Balances::free_balance(&account_id);
+			let appreciation_bonus = pallet_appreciation::AppreciationBonus::<Runtime>::get(community_id)
+				.map(|bonus| bonus as u64);
+
+			Some(CommunityTreasury {
+				community_id,
+				account_id,
+				balance: balance as u64,
+				appreciation_bonus,
+			})
+		}
 	}
 
 	impl runtime_api::nomination_pools::NominationPoolsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
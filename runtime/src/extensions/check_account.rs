@@ -8,10 +8,13 @@ use sp_runtime::{
 	},
 	DispatchResult,
 };
-use sp_std::vec;
+use sp_std::{vec, vec::Vec};
 
 pub type AccountIdentityTag = AccountIdentity;
 
+/// Custom `InvalidTransaction` code for appreciation exceeding rate limits
+pub const APPRECIATION_RATE_LIMIT_EXCEEDED: u8 = 1;
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 #[scale_info(skip_type_params(T))]
 pub struct CheckAccount<T> {
@@ -61,28 +64,47 @@ where
 
 	fn validate(
 		&self,
-		_who: &Self::AccountId,
+		who: &Self::AccountId,
 		call: &Self::Call,
 		_info: &DispatchInfoOf<Self::Call>,
 		_len: usize,
 	) -> TransactionValidity {
 		// In case this is `appreciation` transaction
-		if let Some(to) = call.map_appreciation() {
-			// Check if the user is registered
-			return if T::IdentityProvider::exist_by_identity(&to) {
-				// User already is registered, can execute transaction
-				Ok(ValidTransaction::default())
-			} else {
-				// User is not registered need to provide tag to wait,
-				// until `new_user` transaction provide this tag
-				let requires = vec![Encode::encode(&(to))];
-
-				// These transactions should be stored in the pool for a period of 14 days
-				// `longevity` time sets in blocks
-				let longevity = 14 * DAYS;
-
-				Ok(ValidTransaction { requires, longevity: longevity.into(), ..Default::default() })
+		let recipients = call.map_appreciation();
+		if !recipients.is_empty() {
+			let mut requires = Vec::new();
+
+			for to in recipients {
+				// Check if the user is registered
+				if let Some(info) = T::IdentityProvider::get_identity_info(&to) {
+					// Transaction will fail anyway, do not keep it in the pool
+					if Appreciation::is_rate_limited(who, &info.account_id) {
+						return Err(
+							InvalidTransaction::Custom(APPRECIATION_RATE_LIMIT_EXCEEDED).into()
+						)
+					}
+				} else if !matches!(to, AccountIdentity::PhoneNumberHash(_)) {
+					// User is not registered need to provide tag to wait,
+					// until `new_user` transaction provide this tag.
+					// Appreciation to phone number is escrowed on-chain instead
+					requires.push(Encode::encode(&(to)));
+				}
+			}
+
+			// All users already are registered, can execute transaction
+			if requires.is_empty() {
+				return Ok(ValidTransaction::default())
 			}
+
+			// These transactions should be stored in the pool for a period of 14 days
+			// `longevity` time sets in blocks
+			let longevity = 14 * DAYS;
+
+			return Ok(ValidTransaction {
+				requires,
+				longevity: longevity.into(),
+				..Default::default()
+			})
 		}
 
 		// In case this is `new_user` transaction
@@ -114,21 +136,14 @@ where
 		let now = pallet_timestamp::Pallet::<T>::now();
 
 		// In case this is `appreciation` transaction
-		if let Some(to) = call.map_appreciation() {
-			if let Some(info) = T::IdentityProvider::get_identity_info(&to) {
-				let referral = Identity::get_registration_time(&info.account_id)
-					.map(|registration_time| registration_time >= now)
-					.unwrap_or_default();
-
-				Appreciation::set_referral_flag(referral);
-
-				Ok((now, call.clone()))
-			} else {
-				Err(InvalidTransaction::Custom(u8::MAX).into())
+		for to in call.map_appreciation() {
+			let is_escrowed = matches!(to, AccountIdentity::PhoneNumberHash(_));
+			if !is_escrowed && !T::IdentityProvider::exist_by_identity(&to) {
+				return Err(InvalidTransaction::Custom(u8::MAX).into())
 			}
-		} else {
-			Ok((now, call.clone()))
 		}
+
+		Ok((now, call.clone()))
 	}
 
 	fn post_dispatch(
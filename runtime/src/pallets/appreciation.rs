@@ -1,4 +1,5 @@
 use crate::*;
+use frame_support::PalletId;
 
 parameter_types! {
 	pub const MaxCharTrait: u32 = 100;
@@ -8,6 +9,20 @@ parameter_types! {
 	pub const EmojiLimit: u32 = 20;
 	pub const CommunityUrlLimit: u32 = 100;
 	pub const MaxCommunities: u32 = 1000;
+	pub const InviteExpiration: BlockNumber = 7 * DAYS;
+	pub const ScoreHalvingPeriod: BlockNumber = 3 * MONTHS;
+	pub const ReciprocityWindow: BlockNumber = DAYS;
+	pub const MaxAppreciationsPerPair: u32 = 10;
+	pub const MaxAppreciationsPerPayer: u32 = 100;
+	pub const RateLimitWindow: BlockNumber = DAYS;
+	pub const MemoLimit: u32 = 256;
+	pub const MaxBatchAppreciations: u32 = 50;
+	pub const MaxCommunityCharTraits: u32 = 20;
+	pub const AppreciationPalletId: PalletId = PalletId(*b"kr/aprec");
+	pub const BadgeThresholds: &'static [u32] = &[10, 50, 100];
+	pub const AppreciationUndoPeriod: BlockNumber = 10 * MINUTES;
+	pub const EscrowExpiration: BlockNumber = 14 * DAYS;
+	pub const MaxEscrowedAppreciations: u32 = 10;
 }
 
 impl pallet_appreciation::Config for Runtime {
@@ -27,6 +42,37 @@ impl pallet_appreciation::Config for Runtime {
 	type EmojiLimit = EmojiLimit;
 	/// Max length of `Community`'s urls
 	type CommunityUrlLimit = CommunityUrlLimit;
+	/// Number of blocks after which community invite or join request expires
+	type InviteExpiration = InviteExpiration;
+	/// Number of blocks after which karma score used for karma reward halves
+	type ScoreHalvingPeriod = ScoreHalvingPeriod;
+	/// Each appreciation increases payee's trait score by one
+	type ScoreWeigher = ();
+	/// Number of blocks during which appreciation back to the payer is considered reciprocal
+	type ReciprocityWindow = ReciprocityWindow;
+	/// Max number of appreciations from one payer to one payee during `RateLimitWindow`
+	type MaxAppreciationsPerPair = MaxAppreciationsPerPair;
+	/// Max number of appreciations from one payer during `RateLimitWindow`
+	type MaxAppreciationsPerPayer = MaxAppreciationsPerPayer;
+	/// Number of blocks in appreciations rate limit window
+	type RateLimitWindow = RateLimitWindow;
+	/// Max length of appreciation memo in bytes
+	type MemoLimit = MemoLimit;
+	/// Max number of payees in `batch_appreciation`
+	type MaxBatchAppreciations = MaxBatchAppreciations;
+	/// Max number of custom char traits in one community
+	type MaxCommunityCharTraits = MaxCommunityCharTraits;
+	/// Used to derive community treasury accounts
+	type PalletId = AppreciationPalletId;
+	/// Trait scores at which badges of level 1, 2 and 3 are earned
+	type BadgeThresholds = BadgeThresholds;
+	/// Number of blocks during which payer can undo appreciation
+	type AppreciationUndoPeriod = AppreciationUndoPeriod;
+	/// Number of blocks after which payer can take back funds of appreciation
+	/// to unregistered phone number
+	type EscrowExpiration = EscrowExpiration;
+	/// Max number of escrowed appreciations to one phone number
+	type MaxEscrowedAppreciations = MaxEscrowedAppreciations;
 
 	type IdentityProvider = Identity;
 }
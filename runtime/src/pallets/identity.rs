@@ -1,5 +1,6 @@
 use super::treasury::TreasuryPalletId;
 use crate::*;
+use pallet_identity::traits::UsernameValidator;
 use sp_common::BoundedString;
 
 parameter_types! {
@@ -7,12 +8,39 @@ parameter_types! {
 	pub const PhoneNumberLimit: u32 = 12;
 	pub const MaxPhoneVerifiers: u32 = 5;
 	pub const MaxMetadataLength: u32= 256;
+	pub const UsernameReservationPeriod: BlockNumber = 30 * DAYS;
+	pub const MinUsernameLength: u32 = 3;
+	pub const VerifierNameLimit: u32 = 40;
+	pub const VerifierUrlLimit: u32 = 256;
+	pub const MaxGuardians: u32 = 5;
+	pub const RecoveryExpiration: BlockNumber = 7 * DAYS;
 }
 
 pub type Username = BoundedString<NameLimit>;
 pub type PhoneNumber = BoundedString<PhoneNumberLimit>;
 pub type PhoneNumberHash = sp_core::H512;
 
+/// Accept usernames of at least `MinUsernameLength` characters consisting of latin letters,
+/// digits and `_`, `.`, `-` separators. Username should start and end with letter or digit
+/// and separators can't follow each other. Allowing only ASCII rejects whitespace, control,
+/// zero-width and confusable unicode characters
+pub struct KarmaUsernameValidator;
+
+impl UsernameValidator<Username> for KarmaUsernameValidator {
+	fn is_valid(username: &Username) -> bool {
+		let bytes = username.as_slice();
+		let is_separator = |byte: &u8| matches!(byte, b'_' | b'.' | b'-');
+		let is_allowed =
+			|byte: &u8| byte.is_ascii_lowercase() || byte.is_ascii_digit() || is_separator(byte);
+
+		bytes.len() >= MinUsernameLength::get() as usize &&
+			bytes.iter().all(is_allowed) &&
+			!bytes.first().map_or(true, is_separator) &&
+			!bytes.last().map_or(true, is_separator) &&
+			!bytes.windows(2).any(|pair| is_separator(&pair[0]) && is_separator(&pair[1]))
+	}
+}
+
 impl pallet_identity::Config for Runtime {
 	/// The overarching event type.
 	type RuntimeEvent = RuntimeEvent;
@@ -34,4 +62,18 @@ impl pallet_identity::Config for Runtime {
 	type Treasury = TreasuryPalletId;
 	/// The maximum length of metadata per account.
 	type MaxMetadataLength = MaxMetadataLength;
+	/// Number of blocks during which released username is reserved for the previous owner
+	type UsernameReservationPeriod = UsernameReservationPeriod;
+	/// Rules for usernames
+	type UsernameValidator = KarmaUsernameValidator;
+	/// Max length of phone verifier name and region
+	type VerifierNameLimit = VerifierNameLimit;
+	/// Max length of phone verifier contact URL
+	type VerifierUrlLimit = VerifierUrlLimit;
+	/// Max number of guardians who can recover the account
+	type MaxGuardians = MaxGuardians;
+	/// Number of blocks after which not completed recovery can be replaced
+	type RecoveryExpiration = RecoveryExpiration;
+	/// Guardians should share community with the user
+	type Communities = Appreciation;
 }
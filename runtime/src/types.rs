@@ -65,7 +65,16 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Storage migrations applied on runtime upgrade.
+pub type Migrations = (
+	pallet_appreciation::migrations::v1::MigrateToV1<Runtime>,
+	pallet_appreciation::migrations::v2::MigrateToV2<Runtime>,
+	pallet_appreciation::migrations::v3::MigrateToV3<Runtime>,
+	pallet_appreciation::migrations::v4::MigrateToV4<Runtime>,
+);
+
 pub type AccountIdentity =
 	sp_common::identity::AccountIdentity<AccountId, Username, PhoneNumberHash>;
@@ -43,20 +43,35 @@ impl RuntimeCall {
 	/// Get `AccountIdentity` of recipient of the transaction
 	pub fn get_recipient(&self) -> Option<types::AccountIdentity> {
 		match self {
-			RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation { to, .. }) =>
-				Some(to.clone()),
-			RuntimeCall::Identity(pallet_identity::Call::new_user { account_id, .. }) =>
-				Some(AccountIdentity::AccountId(account_id.clone())),
+			RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation { to, .. }) |
+			RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation_with_memo {
+				to,
+				..
+			}) => Some(to.clone()),
+			RuntimeCall::Identity(pallet_identity::Call::new_user { account_id, .. }) |
+			RuntimeCall::Identity(pallet_identity::Call::new_user_attested {
+				account_id,
+				..
+			}) => Some(AccountIdentity::AccountId(account_id.clone())),
 			// TODO: cover more cases
 			_ => None,
 		}
 	}
 
-	pub fn map_appreciation(&self) -> Option<types::AccountIdentity> {
+	/// Get `AccountIdentity` of all recipients of appreciation transaction.
+	/// Returns empty `Vec` in case this is not an appreciation
+	pub fn map_appreciation(&self) -> Vec<types::AccountIdentity> {
 		match self {
-			RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation { to, .. }) =>
-				Some(to.clone()),
-			_ => None,
+			RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation { to, .. }) |
+			RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation_with_memo {
+				to,
+				..
+			}) => vec![to.clone()],
+			RuntimeCall::Appreciation(pallet_appreciation::Call::batch_appreciation {
+				appreciations,
+				..
+			}) => appreciations.iter().map(|(to, _, _)| to.clone()).collect(),
+			_ => Vec::new(),
 		}
 	}
 
@@ -67,6 +82,12 @@ impl RuntimeCall {
 				phone_number_hash,
 				username,
 				..
+			}) |
+			RuntimeCall::Identity(pallet_identity::Call::new_user_attested {
+				account_id,
+				phone_number_hash,
+				username,
+				..
 			}) => Some((account_id.clone(), username.clone(), *phone_number_hash)),
 			_ => None,
 		}
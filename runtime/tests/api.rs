@@ -180,10 +180,24 @@ mod identity {
 /// `get_identity_by_account`, `get_identity_by_name`, `get_identity_by_number`
 mod community {
 	use crate::utils::{new_test_ext, TestUtils};
-	use karmachain_node_runtime::Runtime;
+	use karmachain_node_runtime::{Appreciation, Runtime};
 	use pallet_appreciation::CommunityRole;
 	use runtime_api::identity::runtime_decl_for_identity_api::IdentityApiV1;
 
+	#[test]
+	fn get_community_treasury() {
+		const COMMUNITY_ID: u32 = 1;
+
+		new_test_ext().with_community(COMMUNITY_ID, "test", true).execute_with(|| {
+			assert!(Runtime::get_community_treasury(COMMUNITY_ID + 1).is_none());
+
+			let treasury = Runtime::get_community_treasury(COMMUNITY_ID).unwrap();
+			assert_eq!(treasury.account_id, Appreciation::community_account_id(COMMUNITY_ID));
+			assert_eq!(treasury.balance, 0);
+			assert_eq!(treasury.appreciation_bonus, None);
+		});
+	}
+
 	#[test]
 	fn get_all_users_community_not_exists() {
 		new_test_ext().execute_with(|| {
@@ -319,8 +333,9 @@ mod community {
 /// Tests API that provides transactions by hash or by account
 /// `get_transactions_by_account`, `get_transaction`
 mod transactions {
-	use crate::utils::{get_account_id_from_seed, new_test_ext, TestUtils};
-	use karmachain_node_runtime::{Hash, Runtime, System};
+	use crate::utils::{get_account_id_from_seed, new_test_ext, next_extrinsic, TestUtils};
+	use frame_support::assert_ok;
+	use karmachain_node_runtime::{Hash, Identity, Runtime, RuntimeOrigin, System};
 	use pallet_appreciation::CommunityRole;
 	use runtime_api::transactions::runtime_decl_for_transaction_indexer::TransactionIndexerV1;
 	use sp_core::sr25519;
@@ -346,11 +361,26 @@ mod transactions {
 		});
 	}
 
-	// TODO: for now this feature is not implemented, so no way to test
 	#[test]
-	#[ignore]
 	fn get_transactions_by_account_update_user_tx() {
-		todo!()
+		new_test_ext().with_user("Bob", "1111").execute_with(|| {
+			let bob_account_id = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+			next_extrinsic();
+			assert_ok!(Identity::update_user(
+				RuntimeOrigin::signed(bob_account_id.clone()),
+				None,
+				None,
+				Some("Bobby".try_into().unwrap()),
+				None,
+			));
+
+			let bob_transactions = Runtime::get_transactions_by_account(bob_account_id);
+			// Bob has
+			// 	+1 for registration
+			//  +1 for update_user
+			assert_eq!(bob_transactions.len(), 2);
+		});
 	}
 
 	#[test]
@@ -413,3 +443,62 @@ mod transactions {
 		});
 	}
 }
+
+/// Tests API that provides information about chain such as `get_char_traits`
+mod chain {
+	use crate::utils::{get_account_id_from_seed, new_test_ext, TestUtils};
+	use frame_support::assert_ok;
+	use karmachain_node_runtime::{Appreciation, Runtime, RuntimeOrigin};
+	use pallet_appreciation::{CharTrait, CommunityRole, FIRST_COMMUNITY_CHAR_TRAIT_ID};
+	use runtime_api::chain::runtime_decl_for_chain_data_provider::ChainDataProviderV1;
+	use sp_core::sr25519;
+
+	const COMMUNITY_ID: u32 = 1;
+
+	#[test]
+	fn get_char_traits_by_community() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				[(1, "kind", "💚"), (2, "smart", "🧠")].into_iter().for_each(
+					|(id, name, emoji)| {
+						pallet_appreciation::CharTraits::<Runtime>::insert(
+							id,
+							CharTrait {
+								id,
+								name: name.try_into().unwrap(),
+								emoji: emoji.try_into().unwrap(),
+							},
+						);
+					},
+				);
+
+				assert_ok!(Appreciation::attach_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					2,
+				));
+				assert_ok!(Appreciation::add_community_char_trait(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					"mentor".try_into().unwrap(),
+					"🎓".try_into().unwrap(),
+				));
+
+				let ids = |community_id| {
+					Runtime::get_char_traits(None, None, community_id)
+						.into_iter()
+						.map(|char_trait| char_trait.id)
+						.collect::<Vec<_>>()
+				};
+
+				// Custom char traits are not global
+				assert_eq!(ids(None), vec![1, 2]);
+				assert_eq!(ids(Some(COMMUNITY_ID)), vec![2, FIRST_COMMUNITY_CHAR_TRAIT_ID]);
+				assert!(ids(Some(COMMUNITY_ID + 1)).is_empty());
+			});
+	}
+}
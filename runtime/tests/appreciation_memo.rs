@@ -0,0 +1,83 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok};
+use karmachain_node_runtime::*;
+use sp_common::identity::AccountIdentity;
+use sp_core::sr25519;
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const CHAR_TRAIT_ID: u32 = 1;
+
+#[test]
+fn kudos_only_appreciation_with_memo() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(1);
+
+			let alice_balance = Balances::free_balance(&alice);
+			let bob_balance = Balances::free_balance(&bob);
+			let memo = "Thanks for help!".try_into().unwrap();
+
+			assert_ok!(Appreciation::appreciation_with_memo(
+				RuntimeOrigin::signed(alice.clone()),
+				AccountIdentity::AccountId(bob.clone()),
+				0,
+				None,
+				Some(CHAR_TRAIT_ID),
+				Some(memo),
+			));
+
+			// No tokens transferred
+			assert_eq!(Balances::free_balance(&alice), alice_balance);
+			assert_eq!(Balances::free_balance(&bob), bob_balance);
+			// But trait score increased
+			assert_eq!(
+				pallet_appreciation::TraitScores::<Runtime>::get((
+					&bob,
+					NO_COMMUNITY_ID,
+					CHAR_TRAIT_ID
+				)),
+				Some(2)
+			);
+			System::assert_has_event(RuntimeEvent::Appreciation(
+				pallet_appreciation::Event::Appreciation {
+					payer: alice,
+					payee: bob,
+					amount: 0,
+					community_id: NO_COMMUNITY_ID,
+					char_trait_id: CHAR_TRAIT_ID,
+					memo: Some("Thanks for help!".try_into().unwrap()),
+				},
+			));
+		});
+}
+
+#[test]
+fn appreciation_with_invalid_memo_fails() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+			assert_noop!(
+				Appreciation::appreciation_with_memo(
+					RuntimeOrigin::signed(alice),
+					AccountIdentity::AccountId(bob),
+					1_000,
+					None,
+					Some(CHAR_TRAIT_ID),
+					Some(vec![0xff, 0xfe].try_into().unwrap()),
+				),
+				pallet_appreciation::Error::<Runtime>::InvalidMemo
+			);
+		});
+}
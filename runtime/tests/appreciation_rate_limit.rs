@@ -0,0 +1,87 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchInfo, traits::Get};
+use karmachain_node_runtime::{
+	extensions::check_account::{CheckAccount, APPRECIATION_RATE_LIMIT_EXCEEDED},
+	*,
+};
+use sp_common::identity::AccountIdentity;
+use sp_core::sr25519;
+use sp_runtime::{
+	traits::SignedExtension, transaction_validity::InvalidTransaction, DispatchResult,
+};
+use utils::*;
+
+fn max_appreciations_per_pair() -> u32 {
+	<Runtime as pallet_appreciation::Config>::MaxAppreciationsPerPair::get()
+}
+
+fn rate_limit_window() -> BlockNumber {
+	<Runtime as pallet_appreciation::Config>::RateLimitWindow::get()
+}
+
+fn appreciate(from: &AccountId, to: &AccountId) -> DispatchResult {
+	Appreciation::appreciation(
+		RuntimeOrigin::signed(from.clone()),
+		AccountIdentity::AccountId(to.clone()),
+		1_000,
+		None,
+		None,
+	)
+}
+
+#[test]
+fn appreciations_between_pair_are_limited_within_window() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.with_balance("Bob", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+			(0..max_appreciations_per_pair()).for_each(|_| assert_ok!(appreciate(&alice, &bob)));
+			assert!(Appreciation::is_rate_limited(&alice, &bob));
+			assert_noop!(
+				appreciate(&alice, &bob),
+				pallet_appreciation::Error::<Runtime>::RateLimitExceeded
+			);
+
+			// Limit applies per pair, Bob still can appreciate Alice
+			assert_ok!(appreciate(&bob, &alice));
+
+			// Counter is reset in the next window
+			System::set_block_number(rate_limit_window());
+			assert!(!Appreciation::is_rate_limited(&alice, &bob));
+			assert_ok!(appreciate(&alice, &bob));
+		});
+}
+
+#[test]
+fn rate_limited_appreciation_is_rejected_by_pool() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+			let call = RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation {
+				to: AccountIdentity::AccountId(bob.clone()),
+				amount: 1_000,
+				community_id: None,
+				char_trait_id: None,
+			});
+			let info = DispatchInfo::default();
+
+			assert_ok!(CheckAccount::<Runtime>::new().validate(&alice, &call, &info, 0));
+
+			(0..max_appreciations_per_pair()).for_each(|_| assert_ok!(appreciate(&alice, &bob)));
+			assert_eq!(
+				CheckAccount::<Runtime>::new().validate(&alice, &call, &info, 0),
+				Err(InvalidTransaction::Custom(APPRECIATION_RATE_LIMIT_EXCEEDED).into())
+			);
+		});
+}
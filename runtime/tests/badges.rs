@@ -0,0 +1,81 @@
+mod utils;
+
+use frame_support::{assert_ok, traits::Get};
+use karmachain_node_runtime::*;
+use sp_core::sr25519;
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const CHAR_TRAIT_ID: u32 = 1;
+
+fn badge_thresholds() -> &'static [u32] {
+	<Runtime as pallet_appreciation::Config>::BadgeThresholds::get()
+}
+
+fn increment_trait_score(account_id: &AccountId, times: u32) {
+	(0..times).for_each(|_| {
+		Appreciation::increment_trait_score(account_id, NO_COMMUNITY_ID, CHAR_TRAIT_ID)
+	});
+}
+
+#[test]
+fn badge_earned_when_score_crosses_threshold() {
+	new_test_ext().execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let threshold = badge_thresholds()[0];
+		System::set_block_number(1);
+
+		increment_trait_score(&alice, threshold - 1);
+		assert!(Appreciation::badges_of(&alice).is_empty());
+
+		increment_trait_score(&alice, 1);
+		assert_eq!(Appreciation::badges_of(&alice), vec![(NO_COMMUNITY_ID, CHAR_TRAIT_ID, 1, 1)]);
+		System::assert_has_event(
+			pallet_appreciation::Event::<Runtime>::BadgeEarned {
+				who: alice.clone(),
+				community_id: NO_COMMUNITY_ID,
+				char_trait_id: CHAR_TRAIT_ID,
+				level: 1,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn badge_earned_only_once() {
+	new_test_ext().execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let threshold = badge_thresholds()[0];
+		System::set_block_number(1);
+
+		increment_trait_score(&alice, threshold);
+		System::set_block_number(2);
+		increment_trait_score(&alice, 1);
+
+		// Badge keeps block number it was earned at
+		assert_eq!(Appreciation::badges_of(&alice), vec![(NO_COMMUNITY_ID, CHAR_TRAIT_ID, 1, 1)]);
+		let badge_events = System::events()
+			.into_iter()
+			.filter(|record| {
+				matches!(
+					record.event,
+					RuntimeEvent::Appreciation(pallet_appreciation::Event::BadgeEarned { .. })
+				)
+			})
+			.count();
+		assert_eq!(badge_events, 1);
+	});
+}
+
+#[test]
+fn badges_removed_with_user() {
+	new_test_ext().with_user("Alice", "1111").execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		increment_trait_score(&alice, badge_thresholds()[0]);
+		assert_eq!(Appreciation::badges_of(&alice).len(), 1);
+
+		assert_ok!(Identity::delete_user(RuntimeOrigin::signed(alice.clone())));
+		assert!(Appreciation::badges_of(&alice).is_empty());
+	});
+}
@@ -0,0 +1,124 @@
+mod utils;
+
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchInfo};
+use karmachain_node_runtime::{extensions::check_account::CheckAccount, *};
+use sp_common::identity::AccountIdentity;
+use sp_core::{hashing::blake2_512, sr25519};
+use sp_runtime::traits::SignedExtension;
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const CHAR_TRAIT_ID: u32 = 1;
+
+fn batch_appreciation_call(
+	appreciations: Vec<(types::AccountIdentity, Balance, Option<u32>)>,
+) -> RuntimeCall {
+	RuntimeCall::Appreciation(pallet_appreciation::Call::batch_appreciation {
+		community_id: None,
+		appreciations: appreciations.try_into().unwrap(),
+	})
+}
+
+#[test]
+fn batch_appreciation_appreciates_each_payee() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_user("Charlie", "3333")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+			let bob_balance = Balances::free_balance(&bob);
+			let charlie_balance = Balances::free_balance(&charlie);
+			let alice_transactions = TransactionIndexer::accounts_tx(&alice).unwrap().len();
+
+			assert_ok!(Appreciation::batch_appreciation(
+				RuntimeOrigin::signed(alice.clone()),
+				None,
+				vec![
+					(AccountIdentity::AccountId(bob.clone()), 1_000, Some(CHAR_TRAIT_ID)),
+					(AccountIdentity::AccountId(charlie.clone()), 2_000, Some(CHAR_TRAIT_ID)),
+				]
+				.try_into()
+				.unwrap(),
+			));
+
+			assert_eq!(Balances::free_balance(&bob), bob_balance + 1_000);
+			assert_eq!(Balances::free_balance(&charlie), charlie_balance + 2_000);
+			[&bob, &charlie].into_iter().for_each(|account_id| {
+				assert_eq!(
+					pallet_appreciation::TraitScores::<Runtime>::get((
+						account_id,
+						NO_COMMUNITY_ID,
+						CHAR_TRAIT_ID
+					)),
+					// Signup trait score and appreciation
+					Some(2)
+				);
+			});
+			// Batch is indexed once for the payer
+			assert_eq!(
+				TransactionIndexer::accounts_tx(&alice).unwrap().len(),
+				alice_transactions + 1
+			);
+		});
+}
+
+#[test]
+fn batch_appreciation_fails_entirely() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			// Charlie is not registered
+			let charlie = "Charlie".try_into().unwrap();
+
+			assert_noop!(
+				Appreciation::batch_appreciation(
+					RuntimeOrigin::signed(alice),
+					None,
+					vec![
+						(AccountIdentity::AccountId(bob), 1_000, Some(CHAR_TRAIT_ID)),
+						(AccountIdentity::Username(charlie), 1_000, Some(CHAR_TRAIT_ID)),
+					]
+					.try_into()
+					.unwrap(),
+				),
+				pallet_appreciation::Error::<Runtime>::NotFound
+			);
+		});
+}
+
+#[test]
+fn batch_appreciation_to_unregistered_user_waits_in_pool() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			let phone_number_hash = PhoneNumberHash::from(blake2_512(b"3333"));
+			let unregistered: types::AccountIdentity =
+				AccountIdentity::Username("Charlie".try_into().unwrap());
+
+			let call = batch_appreciation_call(vec![
+				(AccountIdentity::AccountId(bob), 1_000, None),
+				(unregistered.clone(), 1_000, None),
+				// Appreciation to unregistered phone number is escrowed, so it doesn't wait
+				(AccountIdentity::PhoneNumberHash(phone_number_hash), 1_000, None),
+			]);
+			let validity = CheckAccount::<Runtime>::new()
+				.validate(&alice, &call, &DispatchInfo::default(), 0)
+				.unwrap();
+
+			// Only unregistered username is required
+			assert_eq!(validity.requires, vec![unregistered.encode()]);
+		});
+}
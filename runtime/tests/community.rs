@@ -0,0 +1,1269 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use karmachain_node_runtime::*;
+use pallet_appreciation::CommunityRole;
+use sp_common::identity::AccountIdentity;
+use sp_core::sr25519;
+use utils::*;
+
+const COMMUNITY_ID: u32 = 1;
+const CHAR_TRAIT_ID: u32 = 1;
+
+fn sudo() -> AccountId {
+	get_account_id_from_seed::<sr25519::Public>("Alice")
+}
+
+fn sudo_call(call: pallet_appreciation::Call<Runtime>) {
+	assert_ok!(Sudo::sudo(
+		RuntimeOrigin::signed(sudo()),
+		Box::new(RuntimeCall::Appreciation(call))
+	));
+}
+
+mod lifecycle {
+	use super::*;
+
+	#[test]
+	fn update_community_by_admin() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+
+				assert_ok!(Appreciation::update_community(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					Some("new description".try_into().unwrap()),
+					None,
+					Some("https://karmaco.in".try_into().unwrap()),
+					None,
+					None,
+					None,
+					None,
+					Some(true),
+				));
+
+				let community =
+					pallet_appreciation::Communities::<Runtime>::get(COMMUNITY_ID).unwrap();
+				assert_eq!(community.desc, "new description".try_into().unwrap());
+				assert_eq!(community.website_url, "https://karmaco.in".try_into().unwrap());
+				assert_eq!(community.name, "test".try_into().unwrap());
+				assert!(community.closed);
+			});
+	}
+
+	#[test]
+	fn update_community_by_member_fails() {
+		new_test_ext()
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_noop!(
+					Appreciation::update_community(
+						RuntimeOrigin::signed(bob),
+						COMMUNITY_ID,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						Some(true),
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+			});
+	}
+
+	#[test]
+	fn archived_community_rejects_appreciations() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				sudo_call(pallet_appreciation::Call::<Runtime>::archive_community {
+					community_id: COMMUNITY_ID,
+				});
+				assert!(pallet_appreciation::ArchivedCommunities::<Runtime>::contains_key(
+					COMMUNITY_ID
+				));
+
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+						Some(COMMUNITY_ID),
+						Some(CHAR_TRAIT_ID),
+					),
+					pallet_appreciation::Error::<Runtime>::CommunityArchived
+				);
+				assert_noop!(
+					Appreciation::set_admin(
+						RuntimeOrigin::signed(alice.clone()),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(bob.clone()),
+					),
+					pallet_appreciation::Error::<Runtime>::CommunityArchived
+				);
+
+				// Members and scores are kept while community is archived
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Member)
+				);
+
+				sudo_call(pallet_appreciation::Call::<Runtime>::unarchive_community {
+					community_id: COMMUNITY_ID,
+				});
+				assert!(!pallet_appreciation::ArchivedCommunities::<Runtime>::contains_key(
+					COMMUNITY_ID
+				));
+			});
+	}
+
+	#[test]
+	fn remove_community_cleans_members_and_scores() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				pallet_appreciation::TraitScores::<Runtime>::insert(
+					(&bob, COMMUNITY_ID, CHAR_TRAIT_ID),
+					5,
+				);
+
+				sudo_call(pallet_appreciation::Call::<Runtime>::remove_community {
+					community_id: COMMUNITY_ID,
+					limit: 100,
+				});
+
+				assert!(!pallet_appreciation::Communities::<Runtime>::contains_key(COMMUNITY_ID));
+				assert!(pallet_appreciation::CommunityMembership::<Runtime>::get(
+					&alice,
+					COMMUNITY_ID
+				)
+				.is_none());
+				assert!(pallet_appreciation::CommunityMembership::<Runtime>::get(
+					&bob,
+					COMMUNITY_ID
+				)
+				.is_none());
+				assert!(pallet_appreciation::TraitScores::<Runtime>::iter_prefix((
+					&bob,
+					COMMUNITY_ID
+				))
+				.next()
+				.is_none());
+			});
+	}
+
+	#[test]
+	fn remove_community_in_several_calls() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_user("Charlie", "3333")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.with_community_member(COMMUNITY_ID, "Charlie", CommunityRole::Member)
+			.execute_with(|| {
+				System::set_block_number(1);
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_ok!(Appreciation::remove_community(RuntimeOrigin::root(), COMMUNITY_ID, 1));
+				assert!(!pallet_appreciation::Communities::<Runtime>::contains_key(COMMUNITY_ID));
+				assert!(pallet_appreciation::CommunityRemovals::<Runtime>::contains_key(
+					COMMUNITY_ID
+				));
+
+				let mut calls = 1;
+				while pallet_appreciation::CommunityRemovals::<Runtime>::contains_key(COMMUNITY_ID)
+				{
+					assert!(calls < 100);
+					assert_ok!(Appreciation::remove_community(
+						RuntimeOrigin::root(),
+						COMMUNITY_ID,
+						1
+					));
+					calls += 1;
+				}
+
+				System::assert_last_event(
+					pallet_appreciation::Event::<Runtime>::CommunityRemoved {
+						community_id: COMMUNITY_ID,
+						members: 3,
+					}
+					.into(),
+				);
+				assert!(pallet_appreciation::CommunityMembership::<Runtime>::get(
+					&bob,
+					COMMUNITY_ID
+				)
+				.is_none());
+				assert_noop!(
+					Appreciation::remove_community(RuntimeOrigin::root(), COMMUNITY_ID, 1),
+					pallet_appreciation::Error::<Runtime>::CommunityNotFound
+				);
+			});
+	}
+
+	#[test]
+	fn remove_community_cleans_bans_invites_and_join_requests() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_user("Charlie", "3333")
+			.with_user("Dave", "4444")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+				let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+
+				assert_ok!(Appreciation::ban_member(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+				assert_ok!(Appreciation::invite_member(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(charlie.clone()),
+				));
+				assert_ok!(Appreciation::request_to_join(
+					RuntimeOrigin::signed(dave.clone()),
+					COMMUNITY_ID
+				));
+
+				sudo_call(pallet_appreciation::Call::<Runtime>::remove_community {
+					community_id: COMMUNITY_ID,
+					limit: 100,
+				});
+
+				assert!(!pallet_appreciation::BannedMembers::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+				assert!(!pallet_appreciation::PendingInvites::<Runtime>::contains_key(
+					&charlie,
+					COMMUNITY_ID
+				));
+				assert!(!pallet_appreciation::JoinRequests::<Runtime>::contains_key(
+					&dave,
+					COMMUNITY_ID
+				));
+				assert!(pallet_appreciation::CommunityAccounts::<Runtime>::iter_prefix(
+					COMMUNITY_ID
+				)
+				.next()
+				.is_none());
+			});
+	}
+
+	#[test]
+	fn lifecycle_calls_require_root() {
+		new_test_ext().with_community(COMMUNITY_ID, "test", false).execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+
+			assert_noop!(
+				Appreciation::archive_community(RuntimeOrigin::signed(alice.clone()), COMMUNITY_ID),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Appreciation::remove_community(RuntimeOrigin::signed(alice), COMMUNITY_ID, 100),
+				sp_runtime::DispatchError::BadOrigin
+			);
+		});
+	}
+}
+
+mod membership {
+	use super::*;
+
+	#[test]
+	fn leave_community() {
+		new_test_ext()
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				pallet_appreciation::TraitScores::<Runtime>::insert(
+					(&bob, COMMUNITY_ID, CHAR_TRAIT_ID),
+					5,
+				);
+
+				assert_ok!(Appreciation::leave_community(
+					RuntimeOrigin::signed(bob.clone()),
+					COMMUNITY_ID
+				));
+
+				assert!(!pallet_appreciation::CommunityMembership::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+				assert!(pallet_appreciation::TraitScores::<Runtime>::iter_prefix((
+					&bob,
+					COMMUNITY_ID
+				))
+				.next()
+				.is_none());
+
+				assert_noop!(
+					Appreciation::leave_community(RuntimeOrigin::signed(bob), COMMUNITY_ID),
+					pallet_appreciation::Error::<Runtime>::NotMember
+				);
+			});
+	}
+
+	#[test]
+	fn remove_member_by_admin() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_user("Charlie", "3333")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.with_community_member(COMMUNITY_ID, "Charlie", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+				// Member can't remove other members
+				assert_noop!(
+					Appreciation::remove_member(
+						RuntimeOrigin::signed(bob.clone()),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(alice.clone()),
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+				// Admin can't remove other admins
+				assert_noop!(
+					Appreciation::remove_member(
+						RuntimeOrigin::signed(alice.clone()),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(charlie),
+					),
+					pallet_appreciation::Error::<Runtime>::InsufficientRole
+				);
+
+				assert_ok!(Appreciation::remove_member(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+				assert!(!pallet_appreciation::CommunityMembership::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+			});
+	}
+
+	#[test]
+	fn banned_account_can_not_join_by_appreciation() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				pallet_appreciation::Communities::<Runtime>::mutate(COMMUNITY_ID, |community| {
+					community.as_mut().unwrap().char_traits.try_push(CHAR_TRAIT_ID).unwrap()
+				});
+
+				assert_ok!(Appreciation::ban_member(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+				assert!(!pallet_appreciation::CommunityMembership::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+				assert!(pallet_appreciation::BannedMembers::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+						Some(COMMUNITY_ID),
+						Some(CHAR_TRAIT_ID),
+					),
+					pallet_appreciation::Error::<Runtime>::Banned
+				);
+
+				assert_ok!(Appreciation::unban_member(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice),
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+					Some(COMMUNITY_ID),
+					Some(CHAR_TRAIT_ID),
+				));
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Member)
+				);
+			});
+	}
+}
+
+mod invites {
+	use super::*;
+
+	#[test]
+	fn invite_and_accept() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_noop!(
+					Appreciation::accept_invite(RuntimeOrigin::signed(bob.clone()), COMMUNITY_ID),
+					pallet_appreciation::Error::<Runtime>::InviteNotFound
+				);
+
+				assert_ok!(Appreciation::invite_member(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+				assert_ok!(Appreciation::accept_invite(
+					RuntimeOrigin::signed(bob.clone()),
+					COMMUNITY_ID
+				));
+
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Member)
+				);
+				assert!(!pallet_appreciation::PendingInvites::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+			});
+	}
+
+	#[test]
+	fn expired_invite_can_not_be_accepted() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_ok!(Appreciation::invite_member(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+
+				let expiration = <Runtime as pallet_appreciation::Config>::InviteExpiration::get();
+				System::set_block_number(System::block_number() + expiration + 1);
+
+				assert_noop!(
+					Appreciation::accept_invite(RuntimeOrigin::signed(bob), COMMUNITY_ID),
+					pallet_appreciation::Error::<Runtime>::InviteExpired
+				);
+			});
+	}
+
+	#[test]
+	fn request_to_join_and_approve() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_ok!(Appreciation::request_to_join(
+					RuntimeOrigin::signed(bob.clone()),
+					COMMUNITY_ID
+				));
+				assert_noop!(
+					Appreciation::request_to_join(RuntimeOrigin::signed(bob.clone()), COMMUNITY_ID),
+					pallet_appreciation::Error::<Runtime>::JoinRequestAlreadyExists
+				);
+
+				assert_ok!(Appreciation::approve_join_request(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Member)
+				);
+				assert!(!pallet_appreciation::JoinRequests::<Runtime>::contains_key(
+					&bob,
+					COMMUNITY_ID
+				));
+			});
+	}
+
+	#[test]
+	fn banned_account_can_not_be_invited() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_ok!(Appreciation::ban_member(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+
+				assert_noop!(
+					Appreciation::invite_member(
+						RuntimeOrigin::signed(alice),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(bob.clone()),
+					),
+					pallet_appreciation::Error::<Runtime>::Banned
+				);
+				assert_noop!(
+					Appreciation::request_to_join(RuntimeOrigin::signed(bob), COMMUNITY_ID),
+					pallet_appreciation::Error::<Runtime>::Banned
+				);
+			});
+	}
+}
+
+mod roles {
+	use super::*;
+
+	#[test]
+	fn add_community_sets_owner() {
+		new_test_ext().with_user("Bob", "2222").execute_with(|| {
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+			sudo_call(pallet_appreciation::Call::<Runtime>::add_community {
+				id: COMMUNITY_ID,
+				name: "test".try_into().unwrap(),
+				desc: Default::default(),
+				emoji: Default::default(),
+				website_url: Default::default(),
+				twitter_url: Default::default(),
+				insta_url: Default::default(),
+				face_url: Default::default(),
+				discord_url: Default::default(),
+				char_traits: Default::default(),
+				closed: true,
+				admin: bob.clone(),
+			});
+
+			assert_eq!(
+				pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+				Some(CommunityRole::Owner)
+			);
+		});
+	}
+
+	#[test]
+	fn owner_can_demote_admin() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_user("Charlie", "3333")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Owner)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Charlie", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+				// Admins can't demote each other
+				assert_noop!(
+					Appreciation::set_role(
+						RuntimeOrigin::signed(charlie),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(bob.clone()),
+						CommunityRole::Member,
+					),
+					pallet_appreciation::Error::<Runtime>::InsufficientRole
+				);
+
+				assert_ok!(Appreciation::set_role(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+					CommunityRole::Moderator,
+				));
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Moderator)
+				);
+
+				// Ownership can be changed only with `transfer_ownership`
+				assert_noop!(
+					Appreciation::set_role(
+						RuntimeOrigin::signed(alice),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(bob),
+						CommunityRole::Owner,
+					),
+					pallet_appreciation::Error::<Runtime>::InvalidRole
+				);
+			});
+	}
+
+	#[test]
+	fn moderator_can_invite_but_not_edit() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Moderator)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_ok!(Appreciation::invite_member(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob),
+				));
+				assert_noop!(
+					Appreciation::update_community(
+						RuntimeOrigin::signed(alice),
+						COMMUNITY_ID,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						Some(false),
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+			});
+	}
+
+	#[test]
+	fn transfer_ownership() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Owner)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+				assert_noop!(
+					Appreciation::transfer_ownership(
+						RuntimeOrigin::signed(bob.clone()),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(alice.clone()),
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+
+				assert_ok!(Appreciation::transfer_ownership(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+				));
+
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&alice, COMMUNITY_ID),
+					Some(CommunityRole::Admin)
+				);
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Owner)
+				);
+
+				assert_noop!(
+					Appreciation::leave_community(RuntimeOrigin::signed(bob), COMMUNITY_ID),
+					pallet_appreciation::Error::<Runtime>::OwnerCannotLeave
+				);
+			});
+	}
+
+	#[test]
+	fn set_community_owner() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_user("Charlie", "3333")
+			.with_community(COMMUNITY_ID, "test", true)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+				assert_noop!(
+					Appreciation::set_community_owner(
+						RuntimeOrigin::signed(alice.clone()),
+						COMMUNITY_ID,
+						alice.clone(),
+						None,
+					),
+					sp_runtime::DispatchError::BadOrigin
+				);
+				assert_noop!(
+					Appreciation::set_community_owner(
+						RuntimeOrigin::root(),
+						COMMUNITY_ID,
+						charlie,
+						None,
+					),
+					pallet_appreciation::Error::<Runtime>::NotMember
+				);
+
+				// Community without owner gets one
+				assert_ok!(Appreciation::set_community_owner(
+					RuntimeOrigin::root(),
+					COMMUNITY_ID,
+					alice.clone(),
+					None,
+				));
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&alice, COMMUNITY_ID),
+					Some(CommunityRole::Owner)
+				);
+
+				assert_noop!(
+					Appreciation::set_community_owner(
+						RuntimeOrigin::root(),
+						COMMUNITY_ID,
+						alice.clone(),
+						Some(bob.clone()),
+					),
+					pallet_appreciation::Error::<Runtime>::InsufficientRole
+				);
+				assert_ok!(Appreciation::set_community_owner(
+					RuntimeOrigin::root(),
+					COMMUNITY_ID,
+					bob.clone(),
+					Some(alice.clone()),
+				));
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&alice, COMMUNITY_ID),
+					Some(CommunityRole::Admin)
+				);
+				assert_eq!(
+					pallet_appreciation::CommunityMembership::<Runtime>::get(&bob, COMMUNITY_ID),
+					Some(CommunityRole::Owner)
+				);
+			});
+	}
+}
+
+mod char_traits {
+	use super::*;
+	use sp_runtime::DispatchError;
+
+	const NO_COMMUNITY_ID: u32 = 0;
+	const NEW_CHAR_TRAIT_ID: u32 = 2;
+
+	fn add_char_trait(id: u32, name: &str, emoji: &str) {
+		sudo_call(pallet_appreciation::Call::<Runtime>::add_char_trait {
+			id,
+			name: name.try_into().unwrap(),
+			emoji: emoji.try_into().unwrap(),
+		});
+		assert!(pallet_appreciation::CharTraits::<Runtime>::contains_key(id));
+	}
+
+	#[test]
+	fn char_trait_should_be_valid_utf8() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let invalid_name = || vec![0xff, 0xfe].try_into().unwrap();
+
+				assert_noop!(
+					Appreciation::add_char_trait(
+						RuntimeOrigin::root(),
+						NEW_CHAR_TRAIT_ID,
+						invalid_name(),
+						"💚".try_into().unwrap(),
+					),
+					pallet_appreciation::Error::<Runtime>::InvalidCharTrait
+				);
+				assert_noop!(
+					Appreciation::add_community_char_trait(
+						RuntimeOrigin::signed(alice),
+						COMMUNITY_ID,
+						invalid_name(),
+						"💚".try_into().unwrap(),
+					),
+					pallet_appreciation::Error::<Runtime>::InvalidCharTrait
+				);
+			});
+	}
+
+	#[test]
+	fn admin_attaches_and_detaches_char_trait() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				add_char_trait(NEW_CHAR_TRAIT_ID, "kind", "💚");
+
+				assert_noop!(
+					Appreciation::attach_char_trait(
+						RuntimeOrigin::signed(bob.clone()),
+						COMMUNITY_ID,
+						NEW_CHAR_TRAIT_ID,
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+				assert_ok!(Appreciation::attach_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					NEW_CHAR_TRAIT_ID,
+				));
+				assert_noop!(
+					Appreciation::attach_char_trait(
+						RuntimeOrigin::signed(alice.clone()),
+						COMMUNITY_ID,
+						NEW_CHAR_TRAIT_ID,
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitAlreadyAttached
+				);
+
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice.clone()),
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+					Some(COMMUNITY_ID),
+					Some(NEW_CHAR_TRAIT_ID),
+				));
+
+				assert_ok!(Appreciation::detach_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					NEW_CHAR_TRAIT_ID,
+				));
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+						Some(COMMUNITY_ID),
+						Some(NEW_CHAR_TRAIT_ID),
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitNotFound
+				);
+
+				// Score is kept after trait is detached
+				assert_eq!(
+					pallet_appreciation::TraitScores::<Runtime>::get((
+						&bob,
+						COMMUNITY_ID,
+						NEW_CHAR_TRAIT_ID
+					)),
+					Some(1)
+				);
+			});
+	}
+
+	#[test]
+	fn retired_char_trait_can_not_be_used() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				add_char_trait(NEW_CHAR_TRAIT_ID, "kind", "💚");
+
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice.clone()),
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+					None,
+					Some(NEW_CHAR_TRAIT_ID),
+				));
+
+				assert_noop!(
+					Appreciation::retire_char_trait(
+						RuntimeOrigin::signed(alice.clone()),
+						NEW_CHAR_TRAIT_ID
+					),
+					DispatchError::BadOrigin
+				);
+				sudo_call(pallet_appreciation::Call::<Runtime>::retire_char_trait {
+					char_trait_id: NEW_CHAR_TRAIT_ID,
+				});
+				assert!(pallet_appreciation::RetiredCharTraits::<Runtime>::contains_key(
+					NEW_CHAR_TRAIT_ID
+				));
+
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+						None,
+						Some(NEW_CHAR_TRAIT_ID),
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitRetired
+				);
+				assert_noop!(
+					Appreciation::attach_char_trait(
+						RuntimeOrigin::signed(alice.clone()),
+						COMMUNITY_ID,
+						NEW_CHAR_TRAIT_ID,
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitRetired
+				);
+
+				// Historical score is kept
+				assert_eq!(
+					pallet_appreciation::TraitScores::<Runtime>::get((
+						&bob,
+						NO_COMMUNITY_ID,
+						NEW_CHAR_TRAIT_ID
+					)),
+					Some(1)
+				);
+			});
+	}
+
+	#[test]
+	fn custom_char_trait_is_scoped_to_community() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				add_char_trait(NEW_CHAR_TRAIT_ID, "kind", "💚");
+				let char_trait_id = pallet_appreciation::FIRST_COMMUNITY_CHAR_TRAIT_ID;
+
+				assert_noop!(
+					Appreciation::add_community_char_trait(
+						RuntimeOrigin::signed(bob.clone()),
+						COMMUNITY_ID,
+						"mentor".try_into().unwrap(),
+						"🎓".try_into().unwrap(),
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+				// Name and emoji are validated against global char traits
+				assert_noop!(
+					Appreciation::add_community_char_trait(
+						RuntimeOrigin::signed(alice.clone()),
+						COMMUNITY_ID,
+						"kind".try_into().unwrap(),
+						"🎓".try_into().unwrap(),
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitAlreadyExists
+				);
+				assert_ok!(Appreciation::add_community_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					"mentor".try_into().unwrap(),
+					"🎓".try_into().unwrap(),
+				));
+				assert!(pallet_appreciation::CommunityCharTraits::<Runtime>::contains_key(
+					COMMUNITY_ID,
+					char_trait_id
+				));
+
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice.clone()),
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+					Some(COMMUNITY_ID),
+					Some(char_trait_id),
+				));
+				// Custom char trait can't be used outside of the community
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+						None,
+						Some(char_trait_id),
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitNotFound
+				);
+
+				assert_ok!(Appreciation::remove_community_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					char_trait_id,
+				));
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+						Some(COMMUNITY_ID),
+						Some(char_trait_id),
+					),
+					pallet_appreciation::Error::<Runtime>::CharTraitNotFound
+				);
+			});
+	}
+}
+
+mod treasury {
+	use super::*;
+
+	#[test]
+	fn donate_and_spend_community_funds() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_user("Charlie", "3333")
+			.with_balance("Charlie", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+				let treasury = Appreciation::community_account_id(COMMUNITY_ID);
+
+				assert_ok!(Appreciation::donate_to_community(
+					RuntimeOrigin::signed(charlie),
+					COMMUNITY_ID,
+					10_000,
+				));
+				assert_eq!(Balances::free_balance(&treasury), 10_000);
+
+				assert_noop!(
+					Appreciation::spend_community_funds(
+						RuntimeOrigin::signed(bob.clone()),
+						COMMUNITY_ID,
+						AccountIdentity::AccountId(bob.clone()),
+						1_000,
+					),
+					pallet_appreciation::Error::<Runtime>::NotAdmin
+				);
+
+				let bob_balance = Balances::free_balance(&bob);
+				assert_ok!(Appreciation::spend_community_funds(
+					RuntimeOrigin::signed(alice),
+					COMMUNITY_ID,
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+				));
+				assert_eq!(Balances::free_balance(&treasury), 9_000);
+				assert_eq!(Balances::free_balance(&bob), bob_balance + 1_000);
+			});
+	}
+
+	#[test]
+	fn remove_community_moves_funds_to_treasury() {
+		new_test_ext()
+			.with_user("Charlie", "3333")
+			.with_balance("Charlie", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.execute_with(|| {
+				let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+				let community_treasury = Appreciation::community_account_id(COMMUNITY_ID);
+				let treasury_balance = Balances::free_balance(Treasury::account_id());
+
+				assert_ok!(Appreciation::donate_to_community(
+					RuntimeOrigin::signed(charlie),
+					COMMUNITY_ID,
+					10_000,
+				));
+				sudo_call(pallet_appreciation::Call::<Runtime>::remove_community {
+					community_id: COMMUNITY_ID,
+					limit: 100,
+				});
+
+				assert_eq!(Balances::free_balance(&community_treasury), 0);
+				assert_eq!(
+					Balances::free_balance(Treasury::account_id()),
+					treasury_balance + 10_000
+				);
+			});
+	}
+
+	#[test]
+	fn community_pays_appreciation_bonus() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let treasury = Appreciation::community_account_id(COMMUNITY_ID);
+				let char_trait_id = pallet_appreciation::FIRST_COMMUNITY_CHAR_TRAIT_ID;
+				System::set_block_number(1);
+
+				assert_ok!(Appreciation::add_community_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					"mentor".try_into().unwrap(),
+					"🎓".try_into().unwrap(),
+				));
+				assert_ok!(Appreciation::set_appreciation_bonus(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					Some(500),
+				));
+				assert_ok!(Appreciation::donate_to_community(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					1_000,
+				));
+
+				let bob_balance = Balances::free_balance(&bob);
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice.clone()),
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+					Some(COMMUNITY_ID),
+					Some(char_trait_id),
+				));
+				assert_eq!(Balances::free_balance(&bob), bob_balance + 1_000 + 500);
+				assert_eq!(Balances::free_balance(&treasury), 500);
+				System::assert_has_event(RuntimeEvent::Appreciation(
+					pallet_appreciation::Event::CommunityBonusPaid {
+						community_id: COMMUNITY_ID,
+						payee: bob.clone(),
+						amount: 500,
+					},
+				));
+
+				// Treasury doesn't have enough funds, appreciation still succeeds
+				let bob_balance = Balances::free_balance(&bob);
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice),
+					AccountIdentity::AccountId(bob.clone()),
+					1_000,
+					Some(COMMUNITY_ID),
+					Some(char_trait_id),
+				));
+				assert_eq!(Balances::free_balance(&bob), bob_balance + 1_000);
+				assert_eq!(Balances::free_balance(&treasury), 500);
+			});
+	}
+
+	#[test]
+	fn community_does_not_pay_bonus_for_kudos() {
+		new_test_ext()
+			.with_user("Alice", "1111")
+			.with_user("Bob", "2222")
+			.with_balance("Alice", 1_000_000)
+			.with_community(COMMUNITY_ID, "test", false)
+			.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+			.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+			.execute_with(|| {
+				let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+				let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+				let treasury = Appreciation::community_account_id(COMMUNITY_ID);
+				let char_trait_id = pallet_appreciation::FIRST_COMMUNITY_CHAR_TRAIT_ID;
+
+				assert_ok!(Appreciation::add_community_char_trait(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					"mentor".try_into().unwrap(),
+					"🎓".try_into().unwrap(),
+				));
+				assert_ok!(Appreciation::set_appreciation_bonus(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					Some(500),
+				));
+				assert_ok!(Appreciation::donate_to_community(
+					RuntimeOrigin::signed(alice.clone()),
+					COMMUNITY_ID,
+					1_000,
+				));
+
+				let bob_balance = Balances::free_balance(&bob);
+				assert_ok!(Appreciation::appreciation(
+					RuntimeOrigin::signed(alice),
+					AccountIdentity::AccountId(bob.clone()),
+					0,
+					Some(COMMUNITY_ID),
+					Some(char_trait_id),
+				));
+				assert_eq!(Balances::free_balance(&bob), bob_balance);
+				assert_eq!(Balances::free_balance(&treasury), 1_000);
+			});
+	}
+}
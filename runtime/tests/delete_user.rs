@@ -1,7 +1,8 @@
 mod utils;
 
-use frame_support::assert_ok;
+use frame_support::{assert_noop, assert_ok};
 use karmachain_node_runtime::*;
+use pallet_appreciation::CommunityRole;
 use runtime_api::identity::runtime_decl_for_identity_api::IdentityApiV1;
 use sp_common::identity::AccountIdentity;
 use sp_core::{hashing::blake2_512, sr25519};
@@ -30,8 +31,7 @@ fn delete_user() {
 		assert!(pallet_appreciation::CommunityMembership::<Runtime>::iter_prefix(&account_id)
 			.next()
 			.is_none());
-		pallet_appreciation::Communities::<Runtime>::get().iter().for_each(|community| {
-			let community_id = community.id;
+		pallet_appreciation::Communities::<Runtime>::iter_keys().for_each(|community_id| {
 			let mut trait_score_records = pallet_appreciation::TraitScores::<Runtime>::iter_prefix(
 				(&account_id, community_id),
 			);
@@ -91,3 +91,37 @@ fn delete_user_balance_go_to_treasury() {
 		);
 	})
 }
+
+#[test]
+fn banned_user_stays_banned_after_registering_again() {
+	const COMMUNITY_ID: u32 = 1;
+	let mut test_ext = new_test_ext();
+
+	test_ext
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_community(COMMUNITY_ID, "test", false)
+		.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+		.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+			assert_ok!(Appreciation::ban_member(
+				RuntimeOrigin::signed(alice),
+				COMMUNITY_ID,
+				AccountIdentity::AccountId(bob.clone()),
+			));
+			assert_ok!(Identity::delete_user(RuntimeOrigin::signed(bob)));
+		});
+
+	test_ext.with_user("Bob", "2222").execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+		assert!(pallet_appreciation::BannedMembers::<Runtime>::contains_key(&bob, COMMUNITY_ID));
+		assert_noop!(
+			Appreciation::request_to_join(RuntimeOrigin::signed(bob), COMMUNITY_ID),
+			pallet_appreciation::Error::<Runtime>::Banned
+		);
+	});
+}
@@ -0,0 +1,322 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use karmachain_node_runtime::*;
+use sp_common::identity::AccountIdentity;
+use sp_core::{hashing::blake2_512, sr25519};
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const CHAR_TRAIT_ID: u32 = 1;
+const AMBASSADOR_CHAR_TRAIT_ID: u32 = 41;
+
+fn escrow_expiration() -> BlockNumber {
+	<Runtime as pallet_appreciation::Config>::EscrowExpiration::get()
+}
+
+fn appreciate_phone_number(from: &AccountId, phone_number: &str, amount: Balance) {
+	assert_ok!(Appreciation::appreciation(
+		RuntimeOrigin::signed(from.clone()),
+		AccountIdentity::PhoneNumberHash(PhoneNumberHash::from(blake2_512(
+			phone_number.as_bytes()
+		))),
+		amount,
+		None,
+		Some(CHAR_TRAIT_ID),
+	));
+}
+
+#[test]
+fn escrowed_appreciation_released_on_signup() {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111").with_balance("Alice", 1_000_000);
+
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+	let bob_phone_number_hash = PhoneNumberHash::from(blake2_512(b"2222"));
+
+	ext.execute_with(|| {
+		appreciate_phone_number(&alice, "2222", 1_000);
+
+		assert_eq!(Balances::free_balance(&alice), 999_000);
+		assert_eq!(Balances::free_balance(Appreciation::escrow_account_id()), 1_000);
+		assert!(pallet_appreciation::EscrowedAppreciations::<Runtime>::contains_key(
+			&bob_phone_number_hash,
+			&alice
+		));
+	});
+
+	ext.with_user("Bob", "2222");
+
+	ext.execute_with(|| {
+		assert_eq!(Balances::free_balance(&bob), 1_000);
+		assert_eq!(Balances::free_balance(Appreciation::escrow_account_id()), 0);
+		assert!(pallet_appreciation::EscrowedAppreciations::<Runtime>::iter_prefix(
+			&bob_phone_number_hash
+		)
+		.next()
+		.is_none());
+
+		// Signup and appreciation trait scores
+		assert_eq!(
+			pallet_appreciation::TraitScores::<Runtime>::get((
+				&bob,
+				NO_COMMUNITY_ID,
+				CHAR_TRAIT_ID
+			)),
+			Some(2)
+		);
+		// Released appreciation counts as referral
+		assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), Some(alice.clone()));
+		assert_eq!(
+			pallet_appreciation::TraitScores::<Runtime>::get((
+				&alice,
+				NO_COMMUNITY_ID,
+				AMBASSADOR_CHAR_TRAIT_ID
+			)),
+			Some(1)
+		);
+	});
+}
+
+#[test]
+fn escrowed_appreciation_refunded_after_expiration() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let phone_number_hash = PhoneNumberHash::from(blake2_512(b"2222"));
+			System::set_block_number(1);
+
+			appreciate_phone_number(&alice, "2222", 1_000);
+
+			assert_noop!(
+				Appreciation::refund_escrowed_appreciation(
+					RuntimeOrigin::signed(alice.clone()),
+					phone_number_hash,
+				),
+				pallet_appreciation::Error::<Runtime>::EscrowNotExpired
+			);
+
+			System::set_block_number(escrow_expiration() + 2);
+			assert_ok!(Appreciation::refund_escrowed_appreciation(
+				RuntimeOrigin::signed(alice.clone()),
+				phone_number_hash,
+			));
+
+			assert_eq!(Balances::free_balance(&alice), 1_000_000);
+			System::assert_last_event(
+				pallet_appreciation::Event::<Runtime>::EscrowRefunded {
+					payer: alice.clone(),
+					phone_number_hash,
+					amount: 1_000,
+				}
+				.into(),
+			);
+			assert_noop!(
+				Appreciation::refund_escrowed_appreciation(
+					RuntimeOrigin::signed(alice),
+					phone_number_hash,
+				),
+				pallet_appreciation::Error::<Runtime>::EscrowNotFound
+			);
+		});
+}
+
+#[test]
+fn one_escrow_per_payer_and_phone_number() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+
+			appreciate_phone_number(&alice, "2222", 1_000);
+			assert_noop!(
+				Appreciation::appreciation(
+					RuntimeOrigin::signed(alice),
+					AccountIdentity::PhoneNumberHash(PhoneNumberHash::from(blake2_512(b"2222"))),
+					1_000,
+					None,
+					Some(CHAR_TRAIT_ID),
+				),
+				pallet_appreciation::Error::<Runtime>::EscrowAlreadyExists
+			);
+		});
+}
+
+#[test]
+fn escrow_refunded_when_appreciation_can_not_be_completed() {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111").with_balance("Alice", 1_000_000);
+
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+	ext.execute_with(|| {
+		appreciate_phone_number(&alice, "2222", 1_000);
+		assert_ok!(Appreciation::retire_char_trait(RuntimeOrigin::root(), CHAR_TRAIT_ID));
+	});
+
+	ext.with_user("Bob", "2222");
+
+	ext.execute_with(|| {
+		assert_eq!(Balances::free_balance(&alice), 1_000_000);
+		assert_eq!(Balances::free_balance(&bob), 0);
+		assert_eq!(Balances::free_balance(Appreciation::escrow_account_id()), 0);
+		assert!(pallet_appreciation::PayerEscrows::<Runtime>::iter_prefix(&alice)
+			.next()
+			.is_none());
+	});
+}
+
+#[test]
+fn escrows_of_deleted_payer_go_to_treasury() {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111").with_balance("Alice", 1_000_000);
+
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+	let phone_number_hash = PhoneNumberHash::from(blake2_512(b"2222"));
+
+	ext.execute_with(|| {
+		let treasury_balance = Balances::free_balance(Treasury::account_id());
+		appreciate_phone_number(&alice, "2222", 1_000);
+
+		assert_ok!(Identity::delete_user(RuntimeOrigin::signed(alice.clone())));
+
+		assert!(!pallet_appreciation::EscrowedAppreciations::<Runtime>::contains_key(
+			&phone_number_hash,
+			&alice
+		));
+		assert_eq!(Balances::free_balance(Appreciation::escrow_account_id()), 0);
+		assert_eq!(Balances::free_balance(Treasury::account_id()), treasury_balance + 1_000_000);
+	});
+
+	// Signup isn't affected by escrow of deleted payer
+	ext.with_user("Bob", "2222");
+
+	ext.execute_with(|| {
+		assert!(pallet_identity::IdentityOf::<Runtime>::contains_key(&bob));
+	});
+}
+
+#[test]
+fn earliest_escrowed_appreciation_counts_as_referral() {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111")
+		.with_user("Charlie", "3333")
+		.with_user("Dave", "4444")
+		.with_balance("Alice", 1_000_000)
+		.with_balance("Charlie", 1_000_000)
+		.with_balance("Dave", 1_000_000);
+
+	let payers: Vec<_> = ["Dave", "Alice", "Charlie"]
+		.into_iter()
+		.map(get_account_id_from_seed::<sr25519::Public>)
+		.collect();
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+	ext.execute_with(|| {
+		payers.iter().for_each(|payer| appreciate_phone_number(payer, "2222", 1_000));
+	});
+
+	ext.with_user("Bob", "2222");
+
+	ext.execute_with(|| {
+		assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), Some(payers[0].clone()));
+		assert_eq!(Balances::free_balance(&bob), 3_000);
+	});
+}
+
+#[test]
+fn escrow_below_existential_deposit_fails() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+
+			for amount in [0, EXISTENTIAL_DEPOSIT - 1] {
+				assert_noop!(
+					Appreciation::appreciation(
+						RuntimeOrigin::signed(alice.clone()),
+						AccountIdentity::PhoneNumberHash(PhoneNumberHash::from(blake2_512(
+							b"2222"
+						))),
+						amount,
+						None,
+						Some(CHAR_TRAIT_ID),
+					),
+					pallet_appreciation::Error::<Runtime>::EscrowAmountTooLow
+				);
+			}
+		});
+}
+
+#[test]
+fn escrowed_appreciation_memo_emitted_on_release() {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111").with_balance("Alice", 1_000_000);
+
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Appreciation::appreciation_with_memo(
+			RuntimeOrigin::signed(alice.clone()),
+			AccountIdentity::PhoneNumberHash(PhoneNumberHash::from(blake2_512(b"2222"))),
+			1_000,
+			None,
+			Some(CHAR_TRAIT_ID),
+			Some("Welcome!".try_into().unwrap()),
+		));
+	});
+
+	ext.with_user("Bob", "2222");
+
+	ext.execute_with(|| {
+		System::assert_has_event(RuntimeEvent::Appreciation(
+			pallet_appreciation::Event::Appreciation {
+				payer: alice,
+				payee: bob,
+				amount: 1_000,
+				community_id: NO_COMMUNITY_ID,
+				char_trait_id: CHAR_TRAIT_ID,
+				memo: Some("Welcome!".try_into().unwrap()),
+			},
+		));
+	});
+}
+
+#[test]
+fn escrow_is_rate_limited() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let max_appreciations_per_payer =
+				<Runtime as pallet_appreciation::Config>::MaxAppreciationsPerPayer::get();
+			System::set_block_number(1);
+
+			pallet_appreciation::PayerAppreciations::<Runtime>::insert(
+				&alice,
+				(0, max_appreciations_per_payer),
+			);
+
+			assert_noop!(
+				Appreciation::appreciation(
+					RuntimeOrigin::signed(alice),
+					AccountIdentity::PhoneNumberHash(PhoneNumberHash::from(blake2_512(b"2222"))),
+					1_000,
+					None,
+					Some(CHAR_TRAIT_ID),
+				),
+				pallet_appreciation::Error::<Runtime>::RateLimitExceeded
+			);
+		});
+}
@@ -0,0 +1,139 @@
+mod utils;
+
+use frame_support::{
+	assert_ok,
+	traits::{Get, OnRuntimeUpgrade, StorageVersion},
+};
+use karmachain_node_runtime::*;
+use pallet_appreciation::CommunityRole;
+use runtime_api::identity::runtime_decl_for_identity_api::IdentityApiV1;
+use sp_common::{identity::AccountIdentity, traits::ScoreProvider};
+use sp_core::sr25519;
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const COMMUNITY_ID: u32 = 1;
+const CHAR_TRAIT_ID: u32 = 1;
+
+fn halving_period() -> BlockNumber {
+	<Runtime as pallet_appreciation::Config>::ScoreHalvingPeriod::get()
+}
+
+#[test]
+fn score_halves_each_period() {
+	new_test_ext().execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+
+		(0..4).for_each(|_| {
+			Appreciation::increment_trait_score(&alice, NO_COMMUNITY_ID, CHAR_TRAIT_ID)
+		});
+		assert_eq!(Appreciation::score_of(&alice), 4);
+
+		System::set_block_number(halving_period());
+		assert_eq!(Appreciation::score_of(&alice), 2);
+
+		// New score is added to the decayed one
+		Appreciation::increment_trait_score(&alice, NO_COMMUNITY_ID, CHAR_TRAIT_ID);
+		assert_eq!(Appreciation::score_of(&alice), 3);
+
+		System::set_block_number(3 * halving_period());
+		assert_eq!(Appreciation::score_of(&alice), 0);
+
+		// Lifetime score is kept
+		assert_eq!(
+			Appreciation::trait_scores_of(&alice),
+			vec![(NO_COMMUNITY_ID, CHAR_TRAIT_ID, 5)]
+		);
+	});
+}
+
+#[test]
+fn score_without_decaying_record_decays_from_genesis() {
+	new_test_ext().execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		pallet_appreciation::TraitScores::<Runtime>::insert(
+			(&alice, NO_COMMUNITY_ID, CHAR_TRAIT_ID),
+			8,
+		);
+
+		System::set_block_number(2 * halving_period());
+		assert_eq!(Appreciation::score_of(&alice), 2);
+	});
+}
+
+#[test]
+fn score_earned_before_upgrade_decays_from_upgrade_era() {
+	new_test_ext().execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		pallet_appreciation::TraitScores::<Runtime>::insert(
+			(&alice, NO_COMMUNITY_ID, CHAR_TRAIT_ID),
+			8,
+		);
+
+		System::set_block_number(2 * halving_period());
+		StorageVersion::new(2).put::<Appreciation>();
+		pallet_appreciation::migrations::v3::MigrateToV3::<Runtime>::on_runtime_upgrade();
+
+		assert_eq!(pallet_appreciation::DecayStartEra::<Runtime>::get(), 2);
+		assert_eq!(Appreciation::score_of(&alice), 8);
+
+		System::set_block_number(3 * halving_period());
+		assert_eq!(Appreciation::score_of(&alice), 4);
+	});
+}
+
+#[test]
+fn appreciation_increases_score_by_one_and_remembers_payer() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(5);
+
+			assert_ok!(Appreciation::appreciation(
+				RuntimeOrigin::signed(alice.clone()),
+				AccountIdentity::AccountId(bob.clone()),
+				1_000,
+				None,
+				Some(CHAR_TRAIT_ID),
+			));
+
+			assert_eq!(
+				pallet_appreciation::TraitScores::<Runtime>::get((
+					&bob,
+					NO_COMMUNITY_ID,
+					CHAR_TRAIT_ID
+				)),
+				// Signup trait score and appreciation
+				Some(2)
+			);
+			assert_eq!(
+				pallet_appreciation::LastAppreciationAt::<Runtime>::get(&alice, &bob),
+				Some(5)
+			);
+		});
+}
+
+#[test]
+fn leader_board_karma_score_includes_community_membership() {
+	new_test_ext()
+		.with_user("Bob", "2222")
+		.with_community(COMMUNITY_ID, "test", false)
+		.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+		.execute_with(|| {
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			pallet_reward::KarmaRewardUsersParticipates::<Runtime>::put(1);
+			pallet_reward::AccountRewardInfo::<Runtime>::mutate(&bob, |_| {});
+
+			let user_info =
+				Runtime::get_user_info(AccountIdentity::AccountId(bob.clone())).unwrap();
+			let leader_board = Runtime::get_leader_board();
+
+			assert_eq!(leader_board.len(), 1);
+			assert_eq!(leader_board[0].karma_score, Appreciation::score_of(&bob) + 1);
+			assert_eq!(leader_board[0].karma_score, user_info.karma_score);
+		});
+}
@@ -0,0 +1,254 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use karmachain_node_runtime::*;
+use runtime_api::chain::runtime_decl_for_chain_data_provider::ChainDataProviderV1;
+use sp_core::{ed25519, hashing::blake2_512, sr25519};
+use sp_runtime::{DispatchError, DispatchResult};
+use utils::*;
+
+fn set_phone_verifiers(verifiers: &[&str]) {
+	let verifiers: Vec<_> = verifiers
+		.iter()
+		.map(|seed| get_account_id_from_seed::<ed25519::Public>(seed))
+		.collect();
+	let verifiers: BoundedVec<_, MaxPhoneVerifiers> = verifiers.try_into().unwrap();
+	pallet_identity::PhoneVerifiers::<Runtime>::put(verifiers);
+}
+
+fn try_new_user(verifier: &str, who: &str, phone_number: &str) -> DispatchResult {
+	let account_id = get_account_id_from_seed::<sr25519::Public>(who);
+	let username: Username = who.try_into().unwrap();
+	let phone_number_hash = PhoneNumberHash::from(blake2_512(phone_number.as_bytes()));
+	let (public_key, signature) = get_verification_evidence_from(
+		verifier,
+		account_id.clone(),
+		username.clone(),
+		phone_number_hash,
+	);
+
+	Identity::new_user(
+		RuntimeOrigin::signed(account_id.clone()),
+		public_key,
+		signature,
+		account_id,
+		username,
+		phone_number_hash,
+	)
+}
+
+fn new_user(verifier: &str, who: &str, phone_number: &str) -> AccountId {
+	assert_ok!(try_new_user(verifier, who, phone_number));
+
+	get_account_id_from_seed::<sr25519::Public>(who)
+}
+
+fn add_phone_verifier(seed: &str, name: &str) -> DispatchResult {
+	Identity::add_phone_verifier(
+		RuntimeOrigin::root(),
+		get_account_id_from_seed::<ed25519::Public>(seed),
+		name.try_into().unwrap(),
+		"https://example.com".try_into().unwrap(),
+		"EU".try_into().unwrap(),
+	)
+}
+
+#[test]
+fn new_user_attributed_to_signing_verifier() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_phone_verifiers(&["Alice", "Bob"]);
+		let alice_verifier = get_account_id_from_seed::<ed25519::Public>("Alice");
+		let bob_verifier = get_account_id_from_seed::<ed25519::Public>("Bob");
+
+		let charlie = new_user("Alice", "Charlie", "3333");
+		let dave = new_user("Alice", "Dave", "4444");
+
+		assert_eq!(
+			pallet_identity::VerifiedBy::<Runtime>::get(&charlie),
+			Some(alice_verifier.clone())
+		);
+		assert_eq!(
+			pallet_identity::VerifiedBy::<Runtime>::get(&dave),
+			Some(alice_verifier.clone())
+		);
+		assert_eq!(pallet_identity::VerifierRegistrations::<Runtime>::get(&alice_verifier), 2);
+		assert_eq!(pallet_identity::VerifierRegistrations::<Runtime>::get(&bob_verifier), 0);
+		System::assert_last_event(
+			pallet_identity::Event::<Runtime>::NewUser {
+				phone_verifier: alice_verifier,
+				account_id: dave,
+				username: "dave".try_into().unwrap(),
+				phone_number_hash: PhoneNumberHash::from(blake2_512(b"4444")),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn migration_attributed_to_signing_verifier() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_phone_verifiers(&["Alice", "Bob"]);
+		let bob_verifier = get_account_id_from_seed::<ed25519::Public>("Bob");
+
+		let old_account_id = new_user("Alice", "Charlie", "3333");
+		let new_account_id = get_account_id_from_seed::<sr25519::Public>("Charlie//new");
+		let username: Username = "Charlie".try_into().unwrap();
+		let phone_number_hash = PhoneNumberHash::from(blake2_512(b"3333"));
+		let (public_key, signature) = get_verification_evidence_from(
+			"Bob",
+			new_account_id.clone(),
+			username.clone(),
+			phone_number_hash,
+		);
+
+		assert_ok!(Identity::new_user(
+			RuntimeOrigin::signed(new_account_id.clone()),
+			public_key,
+			signature,
+			new_account_id.clone(),
+			username,
+			phone_number_hash,
+		));
+
+		assert_eq!(pallet_identity::VerifiedBy::<Runtime>::get(&old_account_id), None);
+		assert_eq!(
+			pallet_identity::VerifiedBy::<Runtime>::get(&new_account_id),
+			Some(bob_verifier.clone())
+		);
+		assert_eq!(pallet_identity::VerifierRegistrations::<Runtime>::get(&bob_verifier), 1);
+		System::assert_has_event(
+			pallet_identity::Event::<Runtime>::AccountMigrated {
+				phone_verifier: bob_verifier,
+				old_account_id,
+				new_account_id,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn add_phone_verifier_with_info() {
+	new_test_ext().execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+		let bob_verifier = get_account_id_from_seed::<ed25519::Public>("Bob");
+
+		assert_noop!(
+			Identity::add_phone_verifier(
+				RuntimeOrigin::signed(bob),
+				bob_verifier.clone(),
+				"Bob".try_into().unwrap(),
+				"https://example.com".try_into().unwrap(),
+				"EU".try_into().unwrap(),
+			),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(add_phone_verifier("Bob", "Bob"));
+		assert_noop!(
+			add_phone_verifier("Bob", "Bob"),
+			pallet_identity::Error::<Runtime>::VerifierAlreadyExists
+		);
+		assert_ok!(Identity::set_phone_verifier_info(
+			RuntimeOrigin::root(),
+			bob_verifier.clone(),
+			"Bob verifier".try_into().unwrap(),
+			"https://example.com/bob".try_into().unwrap(),
+			"US".try_into().unwrap(),
+		));
+		new_user("Bob", "Charlie", "3333");
+
+		let verifier = Runtime::get_phone_verifiers()
+			.into_iter()
+			.find(|verifier| verifier.account_id == bob_verifier)
+			.expect("Missing phone verifier");
+		assert_eq!(verifier.name, "Bob verifier");
+		assert_eq!(verifier.url, "https://example.com/bob");
+		assert_eq!(verifier.region, "US");
+		assert!(!verifier.suspended);
+		assert_eq!(verifier.registrations_count, 1);
+	});
+}
+
+#[test]
+fn suspended_phone_verifier_can_not_verify() {
+	new_test_ext().execute_with(|| {
+		let alice_verifier = get_account_id_from_seed::<ed25519::Public>("Alice");
+
+		assert_ok!(Identity::suspend_phone_verifier(RuntimeOrigin::root(), alice_verifier.clone()));
+		assert_noop!(
+			try_new_user("Alice", "Charlie", "3333"),
+			pallet_identity::Error::<Runtime>::VerifierSuspended
+		);
+
+		assert_ok!(Identity::resume_phone_verifier(RuntimeOrigin::root(), alice_verifier));
+		assert_ok!(try_new_user("Alice", "Charlie", "3333"));
+	});
+}
+
+#[test]
+fn rotate_phone_verifier_key() {
+	new_test_ext().execute_with(|| {
+		let alice_verifier = get_account_id_from_seed::<ed25519::Public>("Alice");
+		let new_alice_verifier = get_account_id_from_seed::<ed25519::Public>("Alice//new");
+		new_user("Alice", "Charlie", "3333");
+
+		assert_ok!(Identity::rotate_phone_verifier_key(
+			RuntimeOrigin::root(),
+			alice_verifier.clone(),
+			new_alice_verifier.clone(),
+		));
+
+		assert_noop!(
+			try_new_user("Alice", "Dave", "4444"),
+			pallet_identity::Error::<Runtime>::NotVerifier
+		);
+		new_user("Alice//new", "Dave", "4444");
+		assert_eq!(pallet_identity::VerifierRegistrations::<Runtime>::get(&alice_verifier), 0);
+		assert_eq!(pallet_identity::VerifierRegistrations::<Runtime>::get(&new_alice_verifier), 2);
+	});
+}
+
+#[test]
+fn remove_phone_verifier_keeps_threshold() {
+	new_test_ext().execute_with(|| {
+		let alice_verifier = get_account_id_from_seed::<ed25519::Public>("Alice");
+		let bob_verifier = get_account_id_from_seed::<ed25519::Public>("Bob");
+		assert_ok!(add_phone_verifier("Bob", "Bob"));
+		assert_ok!(Identity::set_verification_threshold(RuntimeOrigin::root(), 2));
+
+		assert_noop!(
+			Identity::remove_phone_verifier(RuntimeOrigin::root(), bob_verifier.clone()),
+			pallet_identity::Error::<Runtime>::InvalidVerificationThreshold
+		);
+
+		assert_ok!(Identity::set_verification_threshold(RuntimeOrigin::root(), 1));
+		assert_ok!(Identity::remove_phone_verifier(RuntimeOrigin::root(), bob_verifier.clone()));
+		assert_eq!(Identity::verifiers().into_inner(), vec![alice_verifier]);
+		assert!(pallet_identity::PhoneVerifierInfo::<Runtime>::get(&bob_verifier).is_none());
+	});
+}
+
+#[test]
+fn suspended_phone_verifiers_do_not_count_for_threshold() {
+	new_test_ext().execute_with(|| {
+		let bob_verifier = get_account_id_from_seed::<ed25519::Public>("Bob");
+		assert_ok!(add_phone_verifier("Bob", "Bob"));
+		assert_ok!(Identity::suspend_phone_verifier(RuntimeOrigin::root(), bob_verifier.clone()));
+
+		assert_noop!(
+			Identity::set_verification_threshold(RuntimeOrigin::root(), 2),
+			pallet_identity::Error::<Runtime>::InvalidVerificationThreshold
+		);
+
+		assert_ok!(Identity::resume_phone_verifier(RuntimeOrigin::root(), bob_verifier.clone()));
+		assert_ok!(Identity::set_verification_threshold(RuntimeOrigin::root(), 2));
+
+		assert_noop!(
+			Identity::suspend_phone_verifier(RuntimeOrigin::root(), bob_verifier),
+			pallet_identity::Error::<Runtime>::InvalidVerificationThreshold
+		);
+	});
+}
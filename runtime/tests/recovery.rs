@@ -0,0 +1,269 @@
+mod utils;
+
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, Get},
+};
+use karmachain_node_runtime::*;
+use pallet_appreciation::CommunityRole;
+use sp_core::sr25519;
+use utils::*;
+
+const COMMUNITY_ID: u32 = 1;
+const CHAR_TRAIT_ID: u32 = 1;
+
+fn account_id(seed: &str) -> AccountId {
+	get_account_id_from_seed::<sr25519::Public>(seed)
+}
+
+fn recovery_expiration() -> BlockNumber {
+	<Runtime as pallet_identity::Config>::RecoveryExpiration::get()
+}
+
+fn guardians(seeds: &[&str]) -> frame_support::BoundedVec<AccountId, MaxGuardians> {
+	seeds
+		.iter()
+		.map(|seed| account_id(seed))
+		.collect::<Vec<_>>()
+		.try_into()
+		.unwrap()
+}
+
+fn new_test_ext_with_community() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_user("Charlie", "3333")
+		.with_user("Dave", "4444")
+		.with_community(COMMUNITY_ID, "test", false)
+		.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Member)
+		.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+		.with_community_member(COMMUNITY_ID, "Charlie", CommunityRole::Member);
+
+	ext
+}
+
+#[test]
+fn guardians_recover_account() {
+	new_test_ext_with_community().execute_with(|| {
+		System::set_block_number(1);
+		let alice = account_id("Alice");
+		let new_alice = account_id("Alice//new");
+		Appreciation::increment_trait_score(&alice, COMMUNITY_ID, CHAR_TRAIT_ID);
+		let balance = Balances::free_balance(&alice);
+
+		assert_ok!(Identity::set_guardians(
+			RuntimeOrigin::signed(alice.clone()),
+			guardians(&["Bob", "Charlie"]),
+			2,
+		));
+		assert_ok!(Identity::initiate_recovery(
+			RuntimeOrigin::signed(account_id("Bob")),
+			alice.clone(),
+			new_alice.clone(),
+		));
+
+		assert_ok!(Identity::approve_recovery(
+			RuntimeOrigin::signed(account_id("Bob")),
+			alice.clone(),
+			new_alice.clone(),
+		));
+		assert_noop!(
+			Identity::approve_recovery(
+				RuntimeOrigin::signed(account_id("Bob")),
+				alice.clone(),
+				new_alice.clone()
+			),
+			pallet_identity::Error::<Runtime>::AlreadyApproved
+		);
+		assert_noop!(
+			Identity::approve_recovery(
+				RuntimeOrigin::signed(account_id("Dave")),
+				alice.clone(),
+				new_alice.clone()
+			),
+			pallet_identity::Error::<Runtime>::NotGuardian
+		);
+		// Guardian approves only recovery to the account they expect
+		assert_noop!(
+			Identity::approve_recovery(
+				RuntimeOrigin::signed(account_id("Charlie")),
+				alice.clone(),
+				account_id("Eve")
+			),
+			pallet_identity::Error::<Runtime>::RecoveryAccountMismatch
+		);
+		assert!(pallet_identity::IdentityOf::<Runtime>::contains_key(&alice));
+
+		assert_ok!(Identity::approve_recovery(
+			RuntimeOrigin::signed(account_id("Charlie")),
+			alice.clone(),
+			new_alice.clone(),
+		));
+
+		System::assert_last_event(
+			pallet_identity::Event::<Runtime>::AccountRecovered {
+				old_account_id: alice.clone(),
+				new_account_id: new_alice.clone(),
+			}
+			.into(),
+		);
+		assert!(pallet_identity::IdentityOf::<Runtime>::get(&alice).is_none());
+		assert_eq!(
+			pallet_identity::IdentityOf::<Runtime>::get(&new_alice)
+				.map(|identity| identity.username),
+			Some("alice".try_into().unwrap())
+		);
+		assert_eq!(Balances::free_balance(&new_alice), balance);
+		assert_eq!(
+			Appreciation::trait_scores_of(&new_alice),
+			vec![(COMMUNITY_ID, CHAR_TRAIT_ID, 1)]
+		);
+		assert!(pallet_identity::ActiveRecoveries::<Runtime>::get(&alice).is_none());
+		assert!(pallet_identity::RecoveryConfigs::<Runtime>::contains_key(&new_alice));
+	});
+}
+
+#[test]
+fn guardians_should_share_community() {
+	new_test_ext_with_community().execute_with(|| {
+		let alice = RuntimeOrigin::signed(account_id("Alice"));
+
+		assert_noop!(
+			Identity::set_guardians(alice.clone(), guardians(&["Bob", "Dave"]), 1),
+			pallet_identity::Error::<Runtime>::GuardianNotInCommunity
+		);
+		assert_noop!(
+			Identity::set_guardians(alice.clone(), guardians(&["Bob", "Alice"]), 1),
+			pallet_identity::Error::<Runtime>::InvalidGuardian
+		);
+		assert_noop!(
+			Identity::set_guardians(alice.clone(), guardians(&["Bob", "Bob"]), 1),
+			pallet_identity::Error::<Runtime>::InvalidGuardian
+		);
+		assert_noop!(
+			Identity::set_guardians(alice.clone(), guardians(&["Bob", "Charlie"]), 3),
+			pallet_identity::Error::<Runtime>::InvalidRecoveryThreshold
+		);
+		assert_noop!(
+			Identity::set_guardians(alice.clone(), guardians(&["Bob", "Charlie"]), 0),
+			pallet_identity::Error::<Runtime>::InvalidRecoveryThreshold
+		);
+		assert_ok!(Identity::set_guardians(alice, guardians(&["Bob", "Charlie"]), 1));
+	});
+}
+
+#[test]
+fn only_guardians_initiate_and_cancel_recovery() {
+	new_test_ext_with_community().execute_with(|| {
+		let alice = account_id("Alice");
+		let bob = account_id("Bob");
+		let eve = account_id("Eve");
+		let new_alice = account_id("Alice//new");
+
+		assert_noop!(
+			Identity::initiate_recovery(
+				RuntimeOrigin::signed(bob.clone()),
+				alice.clone(),
+				new_alice.clone()
+			),
+			pallet_identity::Error::<Runtime>::RecoveryNotConfigured
+		);
+		assert_ok!(Identity::set_guardians(
+			RuntimeOrigin::signed(alice.clone()),
+			guardians(&["Bob"]),
+			1,
+		));
+		assert_noop!(
+			Identity::initiate_recovery(
+				RuntimeOrigin::signed(eve.clone()),
+				alice.clone(),
+				eve.clone()
+			),
+			pallet_identity::Error::<Runtime>::NotGuardian
+		);
+		assert_noop!(
+			Identity::initiate_recovery(
+				RuntimeOrigin::signed(bob.clone()),
+				alice.clone(),
+				account_id("Dave")
+			),
+			pallet_identity::Error::<Runtime>::AlreadyRegistered
+		);
+		assert_ok!(Identity::initiate_recovery(
+			RuntimeOrigin::signed(bob.clone()),
+			alice.clone(),
+			eve.clone()
+		));
+		assert_noop!(
+			Identity::initiate_recovery(
+				RuntimeOrigin::signed(bob.clone()),
+				alice.clone(),
+				new_alice.clone()
+			),
+			pallet_identity::Error::<Runtime>::RecoveryAlreadyActive
+		);
+
+		assert_noop!(
+			Identity::cancel_recovery(RuntimeOrigin::signed(account_id("Dave")), alice.clone()),
+			pallet_identity::Error::<Runtime>::NotGuardian
+		);
+		assert_ok!(Identity::cancel_recovery(RuntimeOrigin::signed(bob.clone()), alice.clone()));
+		assert_noop!(
+			Identity::approve_recovery(RuntimeOrigin::signed(bob.clone()), alice.clone(), eve),
+			pallet_identity::Error::<Runtime>::RecoveryNotActive
+		);
+
+		// Real recovery can be initiated again
+		assert_ok!(Identity::initiate_recovery(
+			RuntimeOrigin::signed(bob),
+			alice.clone(),
+			new_alice,
+		));
+		assert_ok!(Identity::cancel_recovery(RuntimeOrigin::signed(alice.clone()), alice.clone()));
+		assert!(pallet_identity::IdentityOf::<Runtime>::contains_key(&alice));
+	});
+}
+
+#[test]
+fn expired_recovery_can_be_replaced() {
+	new_test_ext_with_community().execute_with(|| {
+		System::set_block_number(1);
+		let alice = account_id("Alice");
+		let bob = account_id("Bob");
+		let new_alice = account_id("Alice//new");
+
+		assert_ok!(Identity::set_guardians(
+			RuntimeOrigin::signed(alice.clone()),
+			guardians(&["Bob"]),
+			1,
+		));
+		assert_ok!(Identity::initiate_recovery(
+			RuntimeOrigin::signed(bob.clone()),
+			alice.clone(),
+			account_id("Eve"),
+		));
+
+		System::set_block_number(recovery_expiration() + 2);
+		assert_noop!(
+			Identity::approve_recovery(
+				RuntimeOrigin::signed(bob.clone()),
+				alice.clone(),
+				account_id("Eve")
+			),
+			pallet_identity::Error::<Runtime>::RecoveryExpired
+		);
+
+		assert_ok!(Identity::initiate_recovery(
+			RuntimeOrigin::signed(bob.clone()),
+			alice.clone(),
+			new_alice.clone(),
+		));
+		assert_ok!(Identity::approve_recovery(
+			RuntimeOrigin::signed(bob),
+			alice.clone(),
+			new_alice.clone(),
+		));
+		assert!(pallet_identity::IdentityOf::<Runtime>::contains_key(&new_alice));
+	});
+}
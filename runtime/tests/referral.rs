@@ -0,0 +1,148 @@
+mod utils;
+
+use frame_support::assert_ok;
+use karmachain_node_runtime::*;
+use sp_common::identity::AccountIdentity;
+use sp_core::sr25519;
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const CHAR_TRAIT_ID: u32 = 1;
+const AMBASSADOR_CHAR_TRAIT_ID: u32 = 41;
+
+fn appreciation_call(to: &AccountId) -> RuntimeCall {
+	RuntimeCall::Appreciation(pallet_appreciation::Call::appreciation {
+		to: AccountIdentity::AccountId(to.clone()),
+		amount: 1_000,
+		community_id: None,
+		char_trait_id: Some(CHAR_TRAIT_ID),
+	})
+}
+
+fn batch_appreciation_call(payees: &[&AccountId]) -> RuntimeCall {
+	let appreciations: Vec<_> = payees
+		.iter()
+		.map(|payee| (AccountIdentity::AccountId((*payee).clone()), 1_000, Some(CHAR_TRAIT_ID)))
+		.collect();
+
+	RuntimeCall::Appreciation(pallet_appreciation::Call::batch_appreciation {
+		community_id: None,
+		appreciations: appreciations.try_into().unwrap(),
+	})
+}
+
+fn ambassador_score(account_id: &AccountId) -> Option<u32> {
+	pallet_appreciation::TraitScores::<Runtime>::get((
+		account_id,
+		NO_COMMUNITY_ID,
+		AMBASSADOR_CHAR_TRAIT_ID,
+	))
+}
+
+#[test]
+fn appreciation_to_just_registered_user_is_referral() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_user("Charlie", "3333")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+			// Bob is registered in the current block
+			assert!(Identity::set_registration_time(&bob, Timestamp::now()));
+
+			assert_ok!(Utility::batch_all(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![appreciation_call(&bob), appreciation_call(&charlie)],
+			));
+
+			assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), Some(alice.clone()));
+			assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&charlie), None);
+			assert_eq!(ambassador_score(&alice), Some(1));
+		});
+}
+
+#[test]
+fn referral_is_counted_once_in_batch() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			assert!(Identity::set_registration_time(&bob, Timestamp::now()));
+
+			assert_ok!(Appreciation::batch_appreciation(
+				RuntimeOrigin::signed(alice.clone()),
+				None,
+				vec![
+					(AccountIdentity::AccountId(bob.clone()), 1_000, Some(CHAR_TRAIT_ID)),
+					(AccountIdentity::AccountId(bob.clone()), 1_000, Some(CHAR_TRAIT_ID)),
+				]
+				.try_into()
+				.unwrap(),
+			));
+
+			assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), Some(alice.clone()));
+			assert_eq!(ambassador_score(&alice), Some(1));
+		});
+}
+
+#[test]
+fn referral_is_counted_once_in_nested_batch() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_user("Charlie", "3333")
+		.with_balance("Alice", 1_000_000)
+		.with_balance("Charlie", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+			assert!(Identity::set_registration_time(&bob, Timestamp::now()));
+
+			assert_ok!(Utility::batch_all(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![appreciation_call(&bob), batch_appreciation_call(&[&bob, &charlie])],
+			));
+
+			// Appreciation from another payer in the same block isn't a referral
+			assert_ok!(Utility::batch_all(
+				RuntimeOrigin::signed(charlie.clone()),
+				vec![batch_appreciation_call(&[&bob])],
+			));
+
+			assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), Some(alice.clone()));
+			assert_eq!(ambassador_score(&alice), Some(1));
+			assert_eq!(ambassador_score(&charlie), None);
+		});
+}
+
+#[test]
+fn appreciation_to_user_registered_earlier_is_not_referral() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			assert!(Identity::set_registration_time(&bob, Timestamp::now()));
+
+			// Next block
+			Timestamp::set_timestamp(Timestamp::now() + 1);
+
+			assert_ok!(Utility::batch_all(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![appreciation_call(&bob)],
+			));
+
+			assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), None);
+			assert_eq!(ambassador_score(&alice), None);
+		});
+}
@@ -0,0 +1,275 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use karmachain_node_runtime::*;
+use pallet_appreciation::CommunityRole;
+use sp_common::identity::AccountIdentity;
+use sp_core::{hashing::blake2_512, sr25519};
+use utils::*;
+
+const NO_COMMUNITY_ID: u32 = 0;
+const CHAR_TRAIT_ID: u32 = 1;
+const COMMUNITY_ID: u32 = 1;
+
+fn undo_period() -> BlockNumber {
+	<Runtime as pallet_appreciation::Config>::AppreciationUndoPeriod::get()
+}
+
+fn appreciate(from: &AccountId, to: &AccountId, amount: Balance) {
+	assert_ok!(Appreciation::appreciation(
+		RuntimeOrigin::signed(from.clone()),
+		AccountIdentity::AccountId(to.clone()),
+		amount,
+		None,
+		Some(CHAR_TRAIT_ID),
+	));
+}
+
+fn undo(from: &AccountId, to: &AccountId) -> sp_runtime::DispatchResult {
+	Appreciation::undo_appreciation(
+		RuntimeOrigin::signed(from.clone()),
+		AccountIdentity::AccountId(to.clone()),
+	)
+}
+
+#[test]
+fn undo_appreciation_returns_tokens_and_scores() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.with_balance("Bob", 1_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(1);
+
+			appreciate(&alice, &bob, 1_000);
+			assert_eq!(Balances::free_balance(&bob), 2_000);
+			assert_eq!(Appreciation::score_of(&bob), 2);
+
+			assert_ok!(undo(&alice, &bob));
+			assert_eq!(Balances::free_balance(&alice), 1_000_000);
+			assert_eq!(Balances::free_balance(&bob), 1_000);
+			// Only signup trait score is left
+			assert_eq!(Appreciation::score_of(&bob), 1);
+			assert_eq!(
+				pallet_appreciation::TraitScores::<Runtime>::get((&alice, NO_COMMUNITY_ID, 2)),
+				Some(0)
+			);
+			System::assert_last_event(
+				pallet_appreciation::Event::<Runtime>::AppreciationRevoked {
+					payer: alice.clone(),
+					payee: bob.clone(),
+					amount: 1_000,
+					community_id: NO_COMMUNITY_ID,
+					char_trait_id: CHAR_TRAIT_ID,
+				}
+				.into(),
+			);
+
+			// Appreciation can be undone only once
+			assert_noop!(
+				undo(&alice, &bob),
+				pallet_appreciation::Error::<Runtime>::AppreciationNotRevocable
+			);
+		});
+}
+
+#[test]
+fn undo_appreciation_of_score_earned_before_decay() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(1);
+			// Score earned before decay was enabled has no decaying record
+			let key = (&bob, NO_COMMUNITY_ID, CHAR_TRAIT_ID);
+			pallet_appreciation::DecayingTraitScores::<Runtime>::remove(key);
+			pallet_appreciation::TraitScores::<Runtime>::insert(key, 8);
+
+			appreciate(&alice, &bob, 1_000);
+			assert_eq!(Appreciation::score_of(&bob), 9);
+
+			assert_ok!(undo(&alice, &bob));
+			assert_eq!(Appreciation::score_of(&bob), 8);
+			assert_eq!(pallet_appreciation::TraitScores::<Runtime>::get(key), Some(8));
+		});
+}
+
+#[test]
+fn undo_appreciation_after_period_fails() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(1);
+
+			appreciate(&alice, &bob, 1_000);
+
+			System::set_block_number(undo_period() + 2);
+			assert_noop!(
+				undo(&alice, &bob),
+				pallet_appreciation::Error::<Runtime>::UndoPeriodExpired
+			);
+		});
+}
+
+#[test]
+fn undo_appreciation_after_payee_moved_funds_fails() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(1);
+
+			appreciate(&alice, &bob, 1_000);
+			assert_ok!(Appreciation::appreciation(
+				RuntimeOrigin::signed(bob.clone()),
+				AccountIdentity::AccountId(alice.clone()),
+				500,
+				None,
+				None,
+			));
+
+			assert_noop!(
+				undo(&alice, &bob),
+				pallet_appreciation::Error::<Runtime>::AppreciationFundsMoved
+			);
+		});
+}
+
+#[test]
+fn undo_appreciation_is_indexed() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			System::set_block_number(1);
+
+			appreciate(&alice, &bob, 1_000);
+			let transactions = TransactionIndexer::accounts_tx(&bob).unwrap_or_default().len();
+
+			next_extrinsic();
+			assert_ok!(undo(&alice, &bob));
+			assert_eq!(
+				TransactionIndexer::accounts_tx(&bob).unwrap_or_default().len(),
+				transactions + 1
+			);
+		});
+}
+
+#[test]
+fn undo_appreciation_decrements_reward_appreciation_count() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			let appreciation_count =
+				|who| pallet_reward::AccountRewardInfo::<Runtime>::get(who).appreciation_count;
+			System::set_block_number(1);
+
+			appreciate(&alice, &bob, 1_000);
+			assert_eq!(appreciation_count(&alice), 1);
+			assert_eq!(appreciation_count(&bob), 1);
+
+			assert_ok!(undo(&alice, &bob));
+			assert_eq!(appreciation_count(&alice), 0);
+			assert_eq!(appreciation_count(&bob), 0);
+		});
+}
+
+#[test]
+fn undo_appreciation_returns_community_bonus() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.with_balance("Alice", 1_000_000)
+		.with_community(COMMUNITY_ID, "test", false)
+		.with_community_member(COMMUNITY_ID, "Alice", CommunityRole::Admin)
+		.with_community_member(COMMUNITY_ID, "Bob", CommunityRole::Member)
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+			let treasury = Appreciation::community_account_id(COMMUNITY_ID);
+			let char_trait_id = pallet_appreciation::FIRST_COMMUNITY_CHAR_TRAIT_ID;
+			System::set_block_number(1);
+
+			assert_ok!(Appreciation::add_community_char_trait(
+				RuntimeOrigin::signed(alice.clone()),
+				COMMUNITY_ID,
+				"mentor".try_into().unwrap(),
+				"🎓".try_into().unwrap(),
+			));
+			assert_ok!(Appreciation::set_appreciation_bonus(
+				RuntimeOrigin::signed(alice.clone()),
+				COMMUNITY_ID,
+				Some(500),
+			));
+			assert_ok!(Appreciation::donate_to_community(
+				RuntimeOrigin::signed(alice.clone()),
+				COMMUNITY_ID,
+				1_000,
+			));
+
+			let bob_balance = Balances::free_balance(&bob);
+			assert_ok!(Appreciation::appreciation(
+				RuntimeOrigin::signed(alice.clone()),
+				AccountIdentity::AccountId(bob.clone()),
+				1_000,
+				Some(COMMUNITY_ID),
+				Some(char_trait_id),
+			));
+			assert_eq!(Balances::free_balance(&treasury), 500);
+
+			assert_ok!(undo(&alice, &bob));
+			assert_eq!(Balances::free_balance(&treasury), 1_000);
+			assert_eq!(Balances::free_balance(&bob), bob_balance);
+		});
+}
+
+#[test]
+fn undo_referral_appreciation_fails() {
+	let mut ext = new_test_ext();
+	ext.with_user("Alice", "1111").with_balance("Alice", 1_000_000);
+
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Appreciation::appreciation(
+			RuntimeOrigin::signed(alice.clone()),
+			AccountIdentity::PhoneNumberHash(PhoneNumberHash::from(blake2_512(b"2222"))),
+			1_000,
+			None,
+			Some(CHAR_TRAIT_ID),
+		));
+	});
+
+	// Escrowed appreciation is released as referral
+	ext.with_user("Bob", "2222");
+
+	ext.execute_with(|| {
+		assert_eq!(pallet_appreciation::Referrers::<Runtime>::get(&bob), Some(alice.clone()));
+		assert_noop!(
+			undo(&alice, &bob),
+			pallet_appreciation::Error::<Runtime>::ReferralNotRevocable
+		);
+	});
+}
@@ -0,0 +1,100 @@
+mod utils;
+
+use frame_support::{assert_ok, dispatch::GetDispatchInfo};
+use karmachain_node_runtime::*;
+use runtime_api::transactions::runtime_decl_for_transaction_indexer::TransactionIndexerV1;
+use sp_common::hooks::Hooks;
+use sp_core::{hashing::blake2_512, sr25519};
+use sp_runtime::DispatchResult;
+use utils::*;
+
+fn phone_number_hash(phone_number: &str) -> PhoneNumberHash {
+	PhoneNumberHash::from(blake2_512(phone_number.as_bytes()))
+}
+
+fn update_phone_number(who: &str, username: &str, phone_number: &str) -> DispatchResult {
+	let account_id = get_account_id_from_seed::<sr25519::Public>(who);
+	let (public_key, signature) = get_verification_evidence(
+		account_id.clone(),
+		username.try_into().unwrap(),
+		phone_number_hash(phone_number),
+	);
+
+	Identity::update_user(
+		RuntimeOrigin::signed(account_id),
+		Some(public_key),
+		Some(signature),
+		None,
+		Some(phone_number_hash(phone_number)),
+	)
+}
+
+#[test]
+fn update_username_is_indexed() {
+	new_test_ext().with_user("Bob", "1111").execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+		next_extrinsic();
+		assert_ok!(Identity::update_user(
+			RuntimeOrigin::signed(bob.clone()),
+			None,
+			None,
+			Some("Bobby".try_into().unwrap()),
+			None,
+		));
+
+		assert_eq!(pallet_transaction_indexer::UpdateUserTransactionsCount::<Runtime>::get(), 1);
+		assert_eq!(Runtime::get_transactions_by_account(bob).len(), 2);
+		assert_eq!(
+			Runtime::get_transactions_by_phone_number_hash(phone_number_hash("1111")).len(),
+			2
+		);
+	});
+}
+
+#[test]
+fn phone_number_history_follows_user() {
+	new_test_ext().with_user("Bob", "1111").execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+		next_extrinsic();
+		assert_ok!(update_phone_number("Bob", "bob", "2222"));
+
+		assert_eq!(pallet_transaction_indexer::UpdateUserTransactionsCount::<Runtime>::get(), 1);
+		assert_eq!(Runtime::get_transactions_by_account(bob.clone()), vec![(0, 1), (0, 2)]);
+		assert!(
+			Runtime::get_transactions_by_phone_number_hash(phone_number_hash("1111")).is_empty()
+		);
+		assert_eq!(
+			Runtime::get_transactions_by_phone_number_hash(phone_number_hash("2222")),
+			vec![(0, 1), (0, 2)]
+		);
+
+		// History is kept after the next phone number change
+		next_extrinsic();
+		assert_ok!(update_phone_number("Bob", "bob", "3333"));
+		assert_eq!(
+			Runtime::get_transactions_by_phone_number_hash(phone_number_hash("3333")),
+			Runtime::get_transactions_by_account(bob)
+		);
+	});
+}
+
+#[test]
+fn update_user_weight_includes_hooks() {
+	let call = pallet_identity::Call::<Runtime>::update_user {
+		verifier_public_key: None,
+		verifier_signature: None,
+		username: Some("bob2".try_into().unwrap()),
+		phone_number_hash: None,
+	};
+	let hooks_weight = <TransactionIndexer as Hooks<
+		AccountId,
+		Balance,
+		Username,
+		PhoneNumberHash,
+	>>::on_update_user_weight();
+
+	assert!(!hooks_weight.is_zero());
+	assert!(call.get_dispatch_info().weight.ref_time() > hooks_weight.ref_time());
+}
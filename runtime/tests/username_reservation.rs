@@ -0,0 +1,122 @@
+mod utils;
+
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Get, Hooks},
+};
+use karmachain_node_runtime::*;
+use pallet_identity::types::VerificationResult;
+use sp_core::{hashing::blake2_512, sr25519};
+use sp_runtime::{DispatchError, DispatchResult};
+use utils::*;
+
+fn reservation_period() -> BlockNumber {
+	<Runtime as pallet_identity::Config>::UsernameReservationPeriod::get()
+}
+
+fn update_username(who: &str, username: &str) -> DispatchResult {
+	Identity::update_user(
+		RuntimeOrigin::signed(get_account_id_from_seed::<sr25519::Public>(who)),
+		None,
+		None,
+		Some(username.try_into().unwrap()),
+		None,
+	)
+}
+
+#[test]
+fn changed_username_is_reserved_for_previous_owner() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(update_username("Alice", "Alice2"));
+
+			assert_noop!(
+				update_username("Bob", "Alice"),
+				pallet_identity::Error::<Runtime>::UsernameReserved
+			);
+
+			// Previous owner can take the username back
+			assert_ok!(update_username("Alice", "Alice"));
+			assert!(pallet_identity::ReleasedUsernames::<Runtime>::get(
+				Username::try_from("alice").unwrap()
+			)
+			.is_none());
+		});
+}
+
+#[test]
+fn released_username_is_free_after_reservation_period() {
+	new_test_ext()
+		.with_user("Alice", "1111")
+		.with_user("Bob", "2222")
+		.execute_with(|| {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			System::set_block_number(1);
+			assert_ok!(Identity::delete_user(RuntimeOrigin::signed(alice)));
+
+			assert_noop!(
+				update_username("Bob", "Alice"),
+				pallet_identity::Error::<Runtime>::UsernameReserved
+			);
+
+			System::set_block_number(reservation_period() + 1);
+			assert_ok!(update_username("Bob", "Alice"));
+		});
+}
+
+#[test]
+fn expired_released_username_is_pruned() {
+	new_test_ext().with_user("Alice", "1111").execute_with(|| {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let username: Username = "alice".try_into().unwrap();
+		System::set_block_number(1);
+		assert_ok!(update_username("Alice", "Alice2"));
+		let reserved_until = 1 + reservation_period();
+
+		assert_eq!(
+			pallet_identity::ReleasedUsernames::<Runtime>::get(&username),
+			Some((alice, reserved_until))
+		);
+
+		Identity::on_initialize(reserved_until - 1);
+		assert!(pallet_identity::ReleasedUsernames::<Runtime>::contains_key(&username));
+
+		Identity::on_initialize(reserved_until);
+		assert!(!pallet_identity::ReleasedUsernames::<Runtime>::contains_key(&username));
+		assert!(pallet_identity::ReleasedUsernameExpirations::<Runtime>::iter_prefix(
+			reserved_until
+		)
+		.next()
+		.is_none());
+	});
+}
+
+#[test]
+fn reserved_username_can_not_be_taken() {
+	new_test_ext().with_user("Bob", "2222").execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let admin: Username = "Admin".try_into().unwrap();
+
+		assert_noop!(
+			Identity::add_reserved_username(RuntimeOrigin::signed(bob), admin.clone()),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Identity::add_reserved_username(RuntimeOrigin::root(), admin.clone()));
+
+		assert_noop!(
+			update_username("Bob", "admin"),
+			pallet_identity::Error::<Runtime>::UsernameReserved
+		);
+		assert!(matches!(
+			Identity::verify(&charlie, &admin, &PhoneNumberHash::from(blake2_512(b"3333"))),
+			VerificationResult::UsernameReserved
+		));
+
+		assert_ok!(Identity::remove_reserved_username(RuntimeOrigin::root(), admin));
+		assert_ok!(update_username("Bob", "admin"));
+	});
+}
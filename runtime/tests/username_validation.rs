@@ -0,0 +1,54 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok};
+use karmachain_node_runtime::*;
+use pallet_identity::types::VerificationResult;
+use sp_core::{hashing::blake2_512, sr25519};
+use sp_runtime::DispatchResult;
+use utils::*;
+
+fn update_username(who: &str, username: &str) -> DispatchResult {
+	Identity::update_user(
+		RuntimeOrigin::signed(get_account_id_from_seed::<sr25519::Public>(who)),
+		None,
+		None,
+		Some(username.try_into().unwrap()),
+		None,
+	)
+}
+
+#[test]
+fn update_user_with_invalid_username_fails() {
+	new_test_ext().with_user("Bob", "2222").execute_with(|| {
+		for username in
+			["bo", "b o b", "_bob", "bob.", "bo__b", "b\u{200b}ob", "b\u{43e}b", "bob\n1"]
+		{
+			assert_noop!(
+				update_username("Bob", username),
+				pallet_identity::Error::<Runtime>::InvalidUsername
+			);
+		}
+	});
+}
+
+#[test]
+fn update_user_with_valid_username() {
+	new_test_ext().with_user("Bob", "2222").execute_with(|| {
+		assert_ok!(update_username("Bob", "bob_1"));
+		assert_ok!(update_username("Bob", " Bob.Smith-2 "));
+		assert_ok!(update_username("Bob", "007"));
+	});
+}
+
+#[test]
+fn verify_invalid_username() {
+	new_test_ext().execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+		let username: Username = "-bob-".try_into().unwrap();
+
+		assert!(matches!(
+			Identity::verify(&bob, &username, &PhoneNumberHash::from(blake2_512(b"2222"))),
+			VerificationResult::InvalidUsername
+		));
+	});
+}
@@ -13,7 +13,9 @@ use sp_common::{
 	BoundedString,
 };
 use sp_core::{ed25519, hashing::blake2_512, sr25519, Pair, Public};
-use sp_rpc::verifier::VerificationEvidence;
+use sp_rpc::verifier::{
+	VerificationEvidence, VerificationEvidenceV2, VersionedVerificationEvidence,
+};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
 /// Generate a crypto pair from seed.
@@ -55,6 +57,9 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		pallet_appreciation::NoCharTraitId::<Runtime>::put(0);
 		// Set default id for SignupTrait
 		pallet_appreciation::SignupCharTraitId::<Runtime>::put(1);
+		// Set default ids for SpenderTrait and AmbassadorTrait
+		pallet_appreciation::SpenderCharTraitId::<Runtime>::put(2);
+		pallet_appreciation::AmbassadorCharTraitId::<Runtime>::put(41);
 		// Set default id for NoCommunity
 		pallet_appreciation::NoCommunityId::<Runtime>::put(0);
 	});
@@ -97,6 +102,7 @@ pub trait TestUtils {
 impl TestUtils for sp_io::TestExternalities {
 	fn with_user(&mut self, username: &str, phone_number: &str) -> &mut Self {
 		self.execute_with(|| {
+			next_extrinsic();
 			let account_id = get_account_id_from_seed::<sr25519::Public>(username);
 			let username = BoundedString::try_from(username).expect("Invalid name length");
 			let phone_number: PhoneNumber =
@@ -146,14 +152,11 @@ impl TestUtils for sp_io::TestExternalities {
 				closed,
 			};
 
-			let mut values = pallet_appreciation::Communities::<Runtime>::get().into_inner();
 			assert!(
-				!values.iter().any(|community| community.id == community_id),
+				!pallet_appreciation::Communities::<Runtime>::contains_key(community_id),
 				"Such community already exists"
 			);
-			values.push(community);
-			let values: BoundedVec<_, _> = values.try_into().unwrap();
-			pallet_appreciation::Communities::<Runtime>::put(values);
+			pallet_appreciation::Communities::<Runtime>::insert(community_id, community);
 		});
 
 		self
@@ -171,10 +174,11 @@ impl TestUtils for sp_io::TestExternalities {
 			let account_id = get_account_id_from_seed::<sr25519::Public>(account_id);
 
 			pallet_appreciation::CommunityMembership::<Runtime>::insert(
-				account_id,
+				&account_id,
 				community_id,
 				role,
 			);
+			pallet_appreciation::CommunityAccounts::<Runtime>::insert(community_id, account_id, ());
 		});
 
 		self
@@ -189,6 +193,7 @@ impl TestUtils for sp_io::TestExternalities {
 		community_id: Option<CommunityId>,
 	) -> &mut Self {
 		self.execute_with(|| {
+			next_extrinsic();
 			let who = get_account_id_from_seed::<sr25519::Public>(who);
 			let to = get_account_id_from_seed::<sr25519::Public>(to);
 
@@ -216,6 +221,7 @@ impl TestUtils for sp_io::TestExternalities {
 
 	fn with_set_admin(&mut self, community_id: CommunityId, who: &str, to: &str) -> &mut Self {
 		self.execute_with(|| {
+			next_extrinsic();
 			let who = get_account_id_from_seed::<sr25519::Public>(who);
 			let to = get_account_id_from_seed::<sr25519::Public>(to);
 
@@ -230,15 +236,35 @@ impl TestUtils for sp_io::TestExternalities {
 	}
 }
 
+/// Move to the next extrinsic in block, so transactions made by helpers
+/// are indexed separately
+pub fn next_extrinsic() {
+	let extrinsic_index = System::extrinsic_index().unwrap_or_default();
+	frame_support::storage::unhashed::put(
+		sp_core::storage::well_known_keys::EXTRINSIC_INDEX,
+		&(extrinsic_index + 1),
+	);
+}
+
 pub fn get_verification_evidence(
 	account_id: AccountId,
 	username: Username,
 	phone_number_hash: PhoneNumberHash,
+) -> (sp_core::ed25519::Public, sp_core::ed25519::Signature) {
+	get_verification_evidence_from("Alice", account_id, username, phone_number_hash)
+}
+
+/// Sign verification evidence by phone verifier generated from `seed`
+pub fn get_verification_evidence_from(
+	seed: &str,
+	account_id: AccountId,
+	username: Username,
+	phone_number_hash: PhoneNumberHash,
 ) -> (sp_core::ed25519::Public, sp_core::ed25519::Signature) {
 	// Cast username to lowercase
 	let username = username.normalize();
 
-	let pair = sp_core::ed25519::Pair::from_string("//Alice", None).unwrap();
+	let pair = sp_core::ed25519::Pair::from_string(&format!("//{seed}"), None).unwrap();
 	let data = VerificationEvidence::<sp_core::ed25519::Public, _, _, _> {
 		verifier_public_key: pair.public(),
 		account_id,
@@ -250,3 +276,34 @@ pub fn get_verification_evidence(
 
 	(pair.public(), signature)
 }
+
+/// Sign `V2` verification evidence by phone verifier generated from `seed`
+pub fn get_verification_evidence_v2(
+	seed: &str,
+	account_id: AccountId,
+	username: Username,
+	phone_number_hash: PhoneNumberHash,
+	nonce: u64,
+	expires_at: BlockNumber,
+	genesis_hash: Hash,
+) -> (sp_core::ed25519::Public, sp_core::ed25519::Signature) {
+	// Cast username to lowercase
+	let username = username.normalize();
+
+	let pair = sp_core::ed25519::Pair::from_string(&format!("//{seed}"), None).unwrap();
+	let data = VersionedVerificationEvidence::<sp_core::ed25519::Public, _, _, _, _, _>::V2(
+		VerificationEvidenceV2 {
+			verifier_public_key: pair.public(),
+			account_id,
+			username,
+			phone_number_hash,
+			nonce,
+			expires_at,
+			genesis_hash,
+		},
+	)
+	.signing_payload();
+	let signature = pair.sign(&data);
+
+	(pair.public(), signature)
+}
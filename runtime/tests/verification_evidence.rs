@@ -0,0 +1,113 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok};
+use karmachain_node_runtime::*;
+use pallet_identity::types::EvidenceVersion;
+use sp_core::{hashing::blake2_512, sr25519};
+use sp_runtime::DispatchResult;
+use utils::*;
+
+fn genesis_hash() -> Hash {
+	System::block_hash(0)
+}
+
+fn new_user_v2(
+	who: &str,
+	nonce: u64,
+	expires_at: BlockNumber,
+	genesis_hash: Hash,
+) -> DispatchResult {
+	let account_id = get_account_id_from_seed::<sr25519::Public>(who);
+	let username: Username = who.try_into().unwrap();
+	let phone_number_hash = PhoneNumberHash::from(blake2_512(b"3333"));
+	let attestation = get_verification_evidence_v2(
+		"Alice",
+		account_id.clone(),
+		username.clone(),
+		phone_number_hash,
+		nonce,
+		expires_at,
+		genesis_hash,
+	);
+
+	Identity::new_user_attested(
+		RuntimeOrigin::signed(account_id.clone()),
+		vec![attestation].try_into().unwrap(),
+		EvidenceVersion::V2 { nonce, expires_at },
+		account_id,
+		username,
+		phone_number_hash,
+	)
+}
+
+#[test]
+fn v2_evidence_can_not_be_replayed() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+		assert_ok!(new_user_v2("Charlie", 1, 10, genesis_hash()));
+		assert_ok!(Identity::delete_user(RuntimeOrigin::signed(charlie)));
+
+		assert_noop!(
+			new_user_v2("Charlie", 1, 10, genesis_hash()),
+			pallet_identity::Error::<Runtime>::EvidenceAlreadyUsed
+		);
+	});
+}
+
+#[test]
+fn expired_v2_evidence_fails() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(11);
+
+		assert_noop!(
+			new_user_v2("Charlie", 1, 10, genesis_hash()),
+			pallet_identity::Error::<Runtime>::EvidenceExpired
+		);
+	});
+}
+
+#[test]
+fn v2_evidence_from_other_chain_fails() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			new_user_v2("Charlie", 1, 10, Hash::repeat_byte(1)),
+			pallet_identity::Error::<Runtime>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn legacy_evidence_rejected_after_minimum_version_raised() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			Identity::set_minimum_evidence_version(RuntimeOrigin::root(), 3),
+			pallet_identity::Error::<Runtime>::InvalidEvidenceVersion
+		);
+		assert_ok!(Identity::set_minimum_evidence_version(RuntimeOrigin::root(), 2));
+
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let username: Username = "charlie".try_into().unwrap();
+		let phone_number_hash = PhoneNumberHash::from(blake2_512(b"3333"));
+		let (public_key, signature) =
+			get_verification_evidence(charlie.clone(), username.clone(), phone_number_hash);
+		assert_noop!(
+			Identity::new_user(
+				RuntimeOrigin::signed(charlie.clone()),
+				public_key,
+				signature,
+				charlie,
+				username,
+				phone_number_hash,
+			),
+			pallet_identity::Error::<Runtime>::EvidenceVersionTooOld
+		);
+
+		assert_ok!(new_user_v2("Charlie", 1, 10, genesis_hash()));
+	});
+}
@@ -0,0 +1,148 @@
+mod utils;
+
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use karmachain_node_runtime::*;
+use pallet_identity::types::EvidenceVersion;
+use sp_core::{ed25519, hashing::blake2_512, sr25519};
+use sp_runtime::DispatchError;
+use utils::*;
+
+fn set_phone_verifiers(verifiers: &[&str], threshold: u32) {
+	let verifiers: Vec<_> = verifiers
+		.iter()
+		.map(|seed| get_account_id_from_seed::<ed25519::Public>(seed))
+		.collect();
+	let verifiers: BoundedVec<_, MaxPhoneVerifiers> = verifiers.try_into().unwrap();
+	pallet_identity::PhoneVerifiers::<Runtime>::put(verifiers);
+	assert_ok!(Identity::set_verification_threshold(RuntimeOrigin::root(), threshold));
+}
+
+fn attestations(
+	verifiers: &[&str],
+	account_id: &AccountId,
+	username: &Username,
+	phone_number_hash: PhoneNumberHash,
+) -> pallet_identity::Attestations<Runtime> {
+	let attestations: Vec<_> = verifiers
+		.iter()
+		.map(|seed| {
+			get_verification_evidence_from(
+				seed,
+				account_id.clone(),
+				username.clone(),
+				phone_number_hash,
+			)
+		})
+		.collect();
+
+	attestations.try_into().unwrap()
+}
+
+#[test]
+fn new_user_requires_threshold_attestations() {
+	new_test_ext().execute_with(|| {
+		set_phone_verifiers(&["Alice", "Bob", "Charlie"], 2);
+
+		let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+		let username: Username = "dave".try_into().unwrap();
+		let phone_number_hash = PhoneNumberHash::from(blake2_512(b"4444"));
+		let (public_key, signature) = get_verification_evidence_from(
+			"Alice",
+			dave.clone(),
+			username.clone(),
+			phone_number_hash,
+		);
+
+		assert_noop!(
+			Identity::new_user(
+				RuntimeOrigin::signed(dave.clone()),
+				public_key,
+				signature,
+				dave.clone(),
+				username.clone(),
+				phone_number_hash,
+			),
+			pallet_identity::Error::<Runtime>::NotEnoughAttestations
+		);
+		assert_noop!(
+			Identity::new_user_attested(
+				RuntimeOrigin::signed(dave.clone()),
+				attestations(&["Alice", "Alice"], &dave, &username, phone_number_hash),
+				EvidenceVersion::V1,
+				dave.clone(),
+				username.clone(),
+				phone_number_hash,
+			),
+			pallet_identity::Error::<Runtime>::DuplicateAttestation
+		);
+
+		assert_ok!(Identity::new_user_attested(
+			RuntimeOrigin::signed(dave.clone()),
+			attestations(&["Bob", "Charlie"], &dave, &username, phone_number_hash),
+			EvidenceVersion::V1,
+			dave.clone(),
+			username,
+			phone_number_hash,
+		));
+		assert_eq!(
+			pallet_identity::VerifiedBy::<Runtime>::get(&dave),
+			Some(get_account_id_from_seed::<ed25519::Public>("Bob"))
+		);
+	});
+}
+
+#[test]
+fn update_phone_number_requires_threshold_attestations() {
+	new_test_ext().with_user("Dave", "4444").execute_with(|| {
+		set_phone_verifiers(&["Alice", "Bob"], 2);
+
+		let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+		let username: Username = "dave".try_into().unwrap();
+		let phone_number_hash = PhoneNumberHash::from(blake2_512(b"5555"));
+
+		assert_noop!(
+			Identity::update_user_attested(
+				RuntimeOrigin::signed(dave.clone()),
+				attestations(&["Alice"], &dave, &username, phone_number_hash),
+				EvidenceVersion::V1,
+				None,
+				Some(phone_number_hash),
+			),
+			pallet_identity::Error::<Runtime>::NotEnoughAttestations
+		);
+		assert_ok!(Identity::update_user_attested(
+			RuntimeOrigin::signed(dave.clone()),
+			attestations(&["Alice", "Bob"], &dave, &username, phone_number_hash),
+			EvidenceVersion::V1,
+			None,
+			Some(phone_number_hash),
+		));
+		assert_eq!(
+			pallet_identity::IdentityOf::<Runtime>::get(&dave)
+				.map(|identity| identity.phone_number_hash),
+			Some(phone_number_hash)
+		);
+	});
+}
+
+#[test]
+fn set_verification_threshold_checks() {
+	new_test_ext().execute_with(|| {
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+		assert_noop!(
+			Identity::set_verification_threshold(RuntimeOrigin::signed(bob), 1),
+			DispatchError::BadOrigin
+		);
+		// Only Alice is phone verifier in test environment
+		assert_noop!(
+			Identity::set_verification_threshold(RuntimeOrigin::root(), 2),
+			pallet_identity::Error::<Runtime>::InvalidVerificationThreshold
+		);
+		assert_noop!(
+			Identity::set_verification_threshold(RuntimeOrigin::root(), 0),
+			pallet_identity::Error::<Runtime>::InvalidVerificationThreshold
+		);
+		assert_ok!(Identity::set_verification_threshold(RuntimeOrigin::root(), 1));
+	});
+}